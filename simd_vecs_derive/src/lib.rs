@@ -0,0 +1,277 @@
+//! `#[derive(Soa)]` for `simd_vecs`.
+//!
+//! Deriving `Soa` on a struct `Particle` generates a `ParticleVec` with one column per field,
+//! along with `ParticleRef` and `ParticleMut` views of a single row. Field types map to columns as:
+//!
+//! * `(T, T)` to `Vec2<T>`
+//! * `(T, T, T)` to `Vec3<T>`
+//! * `[T; N]` to `VecN<T, N>`
+//! * anything else to `Vec1<T>`
+
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, Type};
+
+#[proc_macro_derive(Soa)]
+pub fn derive_soa(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Column {
+    Vec1(Type),
+    Vec2(Type),
+    Vec3(Type),
+    VecN(Type, Expr),
+}
+
+impl Column {
+    fn new(ty: &Type) -> Self {
+        match ty {
+            Type::Tuple(tuple) if same_elements(tuple) && tuple.elems.len() == 2 => Column::Vec2(tuple.elems[0].clone()),
+            Type::Tuple(tuple) if same_elements(tuple) && tuple.elems.len() == 3 => Column::Vec3(tuple.elems[0].clone()),
+            Type::Array(array) => Column::VecN((*array.elem).clone(), array.len.clone()),
+            _ => Column::Vec1(ty.clone()),
+        }
+    }
+
+    fn vec_type(&self) -> TokenStream {
+        match self {
+            Column::Vec1(t) => quote!(::simd_vecs::vecs::Vec1<#t>),
+            Column::Vec2(t) => quote!(::simd_vecs::vecs::Vec2<#t>),
+            Column::Vec3(t) => quote!(::simd_vecs::vecs::Vec3<#t>),
+            Column::VecN(t, n) => quote!(::simd_vecs::vecs::VecN<#t, #n>),
+        }
+    }
+
+    fn ref_type(&self, reference: TokenStream) -> TokenStream {
+        match self {
+            Column::Vec1(t) => quote!(#reference #t),
+            Column::Vec2(t) => quote!((#reference #t, #reference #t)),
+            Column::Vec3(t) => quote!((#reference #t, #reference #t, #reference #t)),
+            Column::VecN(t, n) => quote!([#reference #t; #n]),
+        }
+    }
+
+    fn insert(&self, column: TokenStream, value: &Ident) -> TokenStream {
+        match self {
+            Column::Vec2(_) => quote!(#column.insert(#value.0, #value.1, __soa_index)),
+            Column::Vec3(_) => quote!(#column.insert(#value.0, #value.1, #value.2, __soa_index)),
+            _ => quote!(#column.insert(#value, __soa_index)),
+        }
+    }
+
+    fn iter(&self, column: TokenStream, rows: Ident) -> TokenStream {
+        match self {
+            Column::Vec1(_) if rows == "rows" => quote!(#column.iter()),
+            Column::Vec1(_) => quote!(#column.iter_mut()),
+            Column::Vec2(_) => quote!(#column.#rows().map(|[x, y]| (x, y))),
+            Column::Vec3(_) => quote!(#column.#rows().map(|[x, y, z]| (x, y, z))),
+            Column::VecN(..) => quote!(#column.#rows()),
+        }
+    }
+}
+
+fn same_elements(tuple: &syn::TypeTuple) -> bool {
+    let first = tuple.elems.first().map(|t| quote!(#t).to_string());
+    tuple.elems.iter().all(|t| Some(quote!(#t).to_string()) == first)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "Soa cannot be derived for generic structs"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "Soa requires a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "Soa can only be derived for structs")),
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let vec = format_ident!("{}Vec", name);
+    let row_ref = format_ident!("{}Ref", name);
+    let row_mut = format_ident!("{}Mut", name);
+
+    let names = fields.iter()
+        .map(|f| f.ident.clone().unwrap())
+        .collect::<Vec<_>>();
+    let columns = fields.iter()
+        .map(|f| Column::new(&f.ty))
+        .collect::<Vec<_>>();
+    // locals in the generated methods are prefixed so that they cannot clash with each other or with a field name
+    let bindings = names.iter()
+        .map(|n| format_ident!("__soa_field_{}", n.unraw()))
+        .collect::<Vec<_>>();
+    let appended = bindings.iter().map(|_| quote!(::simd_vecs::vecs::InsertOutcome::Appended));
+    let first = &names[0];
+    let first_binding = &bindings[0];
+
+    let vec_types = columns.iter().map(Column::vec_type);
+    let ref_types = columns.iter().map(|c| c.ref_type(quote!(&'a)));
+    let mut_types = columns.iter().map(|c| c.ref_type(quote!(&'a mut)));
+
+    let inserts = names.iter()
+        .zip(&bindings)
+        .zip(&columns)
+        .map(|((n, b), c)| c.insert(quote!(self.#n), b));
+
+    let rows = Ident::new("rows", Span::call_site());
+    let rows_mut = Ident::new("rows_mut", Span::call_site());
+    let iter = zip(names.iter().zip(&columns).map(|(n, c)| c.iter(quote!(self.#n), rows.clone())));
+    let iter_mut = zip(names.iter().zip(&columns).map(|(n, c)| c.iter(quote!(self.#n), rows_mut.clone())));
+    let pattern = zip_pattern(&names);
+
+    Ok(quote! {
+        #[derive(Default)]
+        #vis struct #vec {
+            #(pub #names: #vec_types,)*
+        }
+
+        #[derive(Copy, Clone)]
+        #vis struct #row_ref<'a> {
+            #(pub #names: #ref_types,)*
+        }
+
+        #vis struct #row_mut<'a> {
+            #(pub #names: #mut_types,)*
+        }
+
+        impl #vec {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn len(&self) -> usize {
+                self.#first.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Returns an error if any column's length differs from the first column's.
+            pub fn check_len(&self) -> Result<(), ::simd_vecs::SimdVecError> {
+                let expected = self.len();
+                #(
+                    if self.#names.len() != expected {
+                        return Err(::simd_vecs::SimdVecError::LengthMismatch { expected, found: self.#names.len() });
+                    }
+                )*
+                Ok(())
+            }
+
+            /// Panics if the column lengths differ.
+            pub fn push(&mut self, value: #name) {
+                let index = self.len();
+                if let ::simd_vecs::vecs::InsertOutcome::OutOfBounds = self.insert(value, index) {
+                    panic!("{}", self.check_len().unwrap_err());
+                }
+            }
+
+            /// Leaves every column unchanged and returns `OutOfBounds` if the column lengths differ.
+            pub fn insert<I: ::simd_vecs::vecs::VecIndex>(&mut self, __soa_value: #name, __soa_index: I) -> ::simd_vecs::vecs::InsertOutcome<#name> {
+                let __soa_index = ::simd_vecs::vecs::VecIndex::index(&__soa_index);
+                if self.check_len().is_err() || __soa_index > self.len() {
+                    return ::simd_vecs::vecs::InsertOutcome::OutOfBounds;
+                }
+
+                let #name { #(#names: #bindings),* } = __soa_value;
+                #(let #bindings = #inserts;)*
+
+                // with equal lengths every column has the same outcome
+                match (#(#bindings,)*) {
+                    (#(::simd_vecs::vecs::InsertOutcome::Replaced(#bindings),)*) => ::simd_vecs::vecs::InsertOutcome::Replaced(#name { #(#names: #bindings),* }),
+                    (#(#appended,)*) => ::simd_vecs::vecs::InsertOutcome::Appended,
+                    _ => unreachable!("columns have equal lengths"),
+                }
+            }
+
+            /// Leaves every column unchanged and returns `None` if the column lengths differ.
+            pub fn swap_remove(&mut self, __soa_index: usize) -> Option<::simd_vecs::vecs::SwapRemoved<#name>> {
+                if self.check_len().is_err() || __soa_index >= self.len() {
+                    return None;
+                }
+
+                #(let #bindings = self.#names.swap_remove(__soa_index).expect("columns have equal lengths");)*
+
+                Some(::simd_vecs::vecs::SwapRemoved {
+                    moved_from: #first_binding.moved_from,
+                    value: #name { #(#names: #bindings.value),* },
+                })
+            }
+
+            /// Leaves every column unchanged and returns `None` if the column lengths differ.
+            pub fn remove(&mut self, __soa_index: usize) -> Option<#name> {
+                if self.check_len().is_err() || __soa_index >= self.len() {
+                    return None;
+                }
+
+                #(let #bindings = self.#names.remove(__soa_index).expect("columns have equal lengths");)*
+
+                Some(#name { #(#names: #bindings),* })
+            }
+
+            pub fn truncate(&mut self, len: usize) {
+                #(self.#names.truncate(len);)*
+            }
+
+            pub fn clear(&mut self) {
+                #(self.#names.clear();)*
+            }
+
+            pub fn get<I: ::simd_vecs::vecs::VecIndex>(&self, index: I) -> Option<#row_ref<'_>> {
+                let index = ::simd_vecs::vecs::VecIndex::index(&index);
+
+                Some(#row_ref {
+                    #(#names: self.#names.get(index)?,)*
+                })
+            }
+
+            pub fn get_mut<I: ::simd_vecs::vecs::VecIndex>(&mut self, index: I) -> Option<#row_mut<'_>> {
+                let index = ::simd_vecs::vecs::VecIndex::index(&index);
+
+                Some(#row_mut {
+                    #(#names: self.#names.get_mut(index)?,)*
+                })
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item=#row_ref<'_>> {
+                #iter.map(|#pattern| #row_ref { #(#names),* })
+            }
+
+            pub fn iter_mut(&mut self) -> impl Iterator<Item=#row_mut<'_>> {
+                #iter_mut.map(|#pattern| #row_mut { #(#names),* })
+            }
+        }
+
+        impl std::iter::FromIterator<#name> for #vec {
+            fn from_iter<I: IntoIterator<Item=#name>>(iter: I) -> Self {
+                let mut vec = Self::new();
+                iter.into_iter().for_each(|value| vec.push(value));
+                vec
+            }
+        }
+    })
+}
+
+/// `a.zip(b).zip(c)`
+fn zip(mut iters: impl Iterator<Item=TokenStream>) -> TokenStream {
+    let first = iters.next().unwrap();
+    iters.fold(first, |acc, next| quote!(#acc.zip(#next)))
+}
+
+/// `((a, b), c)`, matching the items of `zip`
+fn zip_pattern(names: &[Ident]) -> TokenStream {
+    let (first, rest) = names.split_first().unwrap();
+    rest.iter().fold(quote!(#first), |acc, next| quote!((#acc, #next)))
+}
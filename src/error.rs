@@ -0,0 +1,49 @@
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SimdVecError {
+    LengthMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for SimdVecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimdVecError::LengthMismatch { expected, found } => {
+                write!(f, "length mismatch: expected {}, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimdVecError {}
+
+pub(crate) fn check_len(expected: usize, found: usize) -> Result<(), SimdVecError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(SimdVecError::LengthMismatch { expected, found })
+    }
+}
+
+pub(crate) fn check_size(expected: usize, found: Option<usize>) -> Result<(), SimdVecError> {
+    found.map_or(Ok(()), |found| check_len(expected, found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_len_mismatch() {
+        assert_eq!(Ok(()), check_len(3, 3));
+        assert_eq!(Err(SimdVecError::LengthMismatch { expected: 3, found: 2 }), check_len(3, 2));
+        assert_eq!(Ok(()), check_size(3, None));
+    }
+
+    #[test]
+    fn display() {
+        let error = SimdVecError::LengthMismatch { expected: 3, found: 2 };
+
+        assert_eq!("length mismatch: expected 3, found 2", error.to_string());
+    }
+}
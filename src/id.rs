@@ -0,0 +1,234 @@
+use crate::vecs::VecIndex;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A generational handle to a slot in an `Allocator<Tag>`.
+pub struct Id<Tag> {
+    index: usize,
+    generation: u32,
+    marker: PhantomData<fn() -> Tag>,
+}
+
+impl<Tag> Id<Tag> {
+    fn new(index: usize, generation: u32) -> Self {
+        Id {
+            index,
+            generation,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl<Tag> fmt::Debug for Id<Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Id")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl<Tag> Clone for Id<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for Id<Tag> {}
+
+impl<Tag> PartialEq for Id<Tag> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<Tag> Eq for Id<Tag> {}
+
+impl<Tag> Hash for Id<Tag> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+/// An `Id` that has been checked against its allocator.
+///
+/// The borrow of the allocator guarantees that the id cannot be killed while this exists.
+pub struct Valid<'a, Tag> {
+    id: Id<Tag>,
+    marker: PhantomData<&'a Allocator<Tag>>,
+}
+
+impl<'a, Tag> Valid<'a, Tag> {
+    pub fn id(&self) -> Id<Tag> {
+        self.id
+    }
+}
+
+impl<'a, Tag> fmt::Debug for Valid<'a, Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Valid").field(&self.id).finish()
+    }
+}
+
+impl<'a, Tag> Clone for Valid<'a, Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Tag> Copy for Valid<'a, Tag> {}
+
+impl<'a, Tag> VecIndex for Valid<'a, Tag> {
+    fn index(&self) -> usize {
+        self.id.index
+    }
+}
+
+/// Hands out `Id<Tag>` handles, reusing the slots of killed ids.
+///
+/// Each slot carries a generation that is bumped when its id is killed,
+/// so handles to a reused slot are detected as stale.
+pub struct Allocator<Tag> {
+    generations: Vec<u32>,
+    free: Vec<usize>,
+    marker: PhantomData<fn() -> Tag>,
+}
+
+impl<Tag> Default for Allocator<Tag> {
+    fn default() -> Self {
+        Allocator {
+            generations: vec![],
+            free: vec![],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Tag> fmt::Debug for Allocator<Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Allocator")
+            .field("generations", &self.generations)
+            .field("free", &self.free)
+            .finish()
+    }
+}
+
+impl<Tag> Allocator<Tag> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(&mut self) -> Id<Tag> {
+        if let Some(index) = self.free.pop() {
+            Id::new(index, self.generations[index])
+        } else {
+            self.generations.push(0);
+            Id::new(self.generations.len() - 1, 0)
+        }
+    }
+
+    pub fn kill(&mut self, id: Id<Tag>) -> bool {
+        if self.is_alive(id) {
+            let generation = &mut self.generations[id.index];
+            *generation = generation.wrapping_add(1);
+            self.free.push(id.index);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_alive(&self, id: Id<Tag>) -> bool {
+        self.generations.get(id.index) == Some(&id.generation)
+    }
+
+    pub fn validate(&self, id: Id<Tag>) -> Option<Valid<'_, Tag>> {
+        if self.is_alive(id) {
+            Some(Valid {
+                id,
+                marker: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The number of slots, living or dead, which is the length needed for columns indexed by these ids.
+    pub fn slots(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub fn living(&self) -> usize {
+        self.generations.len() - self.free.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vecs::*;
+
+    #[derive(Debug)]
+    struct Body;
+
+    #[test]
+    fn create_reuses_killed_slots() {
+        let mut alloc = Allocator::<Body>::new();
+
+        let a = alloc.create();
+        let b = alloc.create();
+
+        assert!(alloc.kill(a));
+        assert!(!alloc.kill(a));
+
+        let c = alloc.create();
+
+        assert_eq!(a.index(), c.index());
+        assert_ne!(a, c);
+        assert!(!alloc.is_alive(a));
+        assert!(alloc.is_alive(b));
+        assert!(alloc.is_alive(c));
+        assert_eq!(2, alloc.slots());
+        assert_eq!(2, alloc.living());
+    }
+
+    #[test]
+    fn index_vecs_by_valid_id() {
+        let mut alloc = Allocator::<Body>::new();
+        let mut mass = Vec1::new();
+        let mut position = Vec2::new();
+
+        let a = alloc.create();
+        let b = alloc.create();
+
+        for (id, m) in [(a, 1.0), (b, 2.0)].iter() {
+            let id = alloc.validate(*id).unwrap();
+            assert_eq!(InsertOutcome::Appended, mass.insert(*m, id));
+            assert_eq!(InsertOutcome::Appended, position.insert(*m, -*m, id));
+        }
+
+        alloc.kill(a);
+        assert!(alloc.validate(a).is_none());
+
+        let c = alloc.create();
+        let c = alloc.validate(c).unwrap();
+        assert_eq!(InsertOutcome::Replaced(1.0), mass.insert(3.0, c));
+
+        if let Some(m) = mass.get_mut(c) {
+            *m *= 2.0;
+        }
+
+        assert_eq!(Some(&6.0), mass.get(c));
+        assert_eq!(Some((&2.0, &-2.0)), position.get(alloc.validate(b).unwrap()));
+    }
+}
@@ -0,0 +1,492 @@
+use crate::vecs::Vec1;
+use std::fmt;
+
+const C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+
+const A: [&[f64]; 7] = [
+    &[],
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+    &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+    &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+
+/// The fifth-order weights minus the embedded fourth-order weights.
+const E: [f64; 7] = [71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0];
+
+/// Dense output weights from Hairer's DOPRI5.
+const D: [f64; 7] = [
+    -12715105075.0 / 11282082432.0,
+    0.0,
+    87487479700.0 / 32700410799.0,
+    -10690763975.0 / 1880347072.0,
+    701980252875.0 / 199316789632.0,
+    -1453857185.0 / 822651844.0,
+    69997945.0 / 29380423.0,
+];
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+/// How the error estimates of each element are combined into the error of a step.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ErrorNorm {
+    /// The root mean square over every element of every column, as in Hairer's DOPRI5,
+    /// so that a few elements may exceed the tolerance if the rest are well within it.
+    Global,
+    /// The largest error of any element, so that every element meets the tolerance on its own.
+    PerElement,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SolverError {
+    /// The step size became too small to advance `t`.
+    StepSizeTooSmall { t: f64 },
+    /// `max_steps` steps were taken without reaching the end time.
+    TooManySteps { t: f64 },
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::StepSizeTooSmall { t } => write!(f, "step size too small at t = {}", t),
+            SolverError::TooManySteps { t } => write!(f, "too many steps, stopped at t = {}", t),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Stats {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub evaluations: usize,
+}
+
+/// The interpolating polynomial of the last accepted step, from `t` to `end`.
+#[derive(Debug, Default, Clone)]
+struct Dense {
+    t: f64,
+    end: f64,
+    h: f64,
+    r: [Vec<Vec1<f64>>; 5],
+}
+
+/// The Dormand-Prince 5(4) method with adaptive step sizes and dense output, for first-order
+/// systems `y' = f(t, y)` whose state is one or more `Vec1<f64>` columns.
+///
+/// The derivative `f(t, y, dydt)` overwrites every element of `dydt`, which has the same
+/// column lengths as `y`. Steps are accepted when the error estimate, scaled by
+/// `atol + rtol * |y|`, has a norm of at most one.
+#[derive(Debug, Clone)]
+pub struct DormandPrince {
+    pub rtol: f64,
+    pub atol: f64,
+    pub norm: ErrorNorm,
+    /// The maximum number of steps per call to `solve`.
+    pub max_steps: usize,
+    /// The size of the next step, or zero to choose one from the derivatives.
+    h: f64,
+    k: [Vec<Vec1<f64>>; 7],
+    y: Vec<Vec1<f64>>,
+    /// Whether `k[0]` holds the derivative at the current state.
+    fsal: bool,
+    dense: Dense,
+    stats: Stats,
+}
+
+impl DormandPrince {
+    pub fn new(rtol: f64, atol: f64) -> Self {
+        DormandPrince {
+            rtol,
+            atol,
+            norm: ErrorNorm::Global,
+            max_steps: 100_000,
+            h: 0.0,
+            k: Default::default(),
+            y: vec![],
+            fsal: false,
+            dense: Dense::default(),
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Forgets the step size and dense output, e.g. before solving a different problem.
+    pub fn reset(&mut self) {
+        self.h = 0.0;
+        self.dense.h = 0.0;
+        self.stats = Stats::default();
+    }
+
+    /// Integrates `y` from `t` to `t_end`, leaving `t == t_end`.
+    pub fn solve<F>(&mut self, t: &mut f64, y: &mut [Vec1<f64>], t_end: f64, mut f: F) -> Result<(), SolverError>
+        where F: FnMut(f64, &[Vec1<f64>], &mut [Vec1<f64>])
+    {
+        self.fsal = false;
+
+        for _ in 0..self.max_steps {
+            if *t >= t_end {
+                return Ok(());
+            }
+            self.advance(t, y, t_end, &mut f)?;
+        }
+
+        if *t >= t_end {
+            Ok(())
+        } else {
+            Err(SolverError::TooManySteps { t: *t })
+        }
+    }
+
+    /// Takes one accepted step towards `t_end`, retrying with smaller steps as needed.
+    /// The dense output then covers the step. Does nothing once `t` has reached `t_end`.
+    pub fn step<F>(&mut self, t: &mut f64, y: &mut [Vec1<f64>], t_end: f64, mut f: F) -> Result<(), SolverError>
+        where F: FnMut(f64, &[Vec1<f64>], &mut [Vec1<f64>])
+    {
+        self.fsal = false;
+        self.advance(t, y, t_end, &mut f)
+    }
+
+    /// Writes the state at `t`, which must lie within the last accepted step, into `out`.
+    pub fn interpolate(&self, t: f64, out: &mut [Vec1<f64>]) {
+        let Dense { t: t0, end, h, r } = &self.dense;
+        assert!(*h > 0.0, "no step has been taken");
+        assert!(*t0 <= t && t <= *end, "{} is outside the last step [{}, {}]", t, t0, end);
+        assert_eq!(r[0].len(), out.len(), "column count mismatch");
+
+        let theta = ((t - t0) / h).min(1.0);
+        let theta1 = 1.0 - theta;
+
+        for (c, out) in out.iter_mut().enumerate() {
+            out.values.clear();
+            out.values.extend((0..r[0][c].len()).map(|i| {
+                let [r0, r1, r2, r3, r4] = [0, 1, 2, 3, 4].map(|n| r[n][c].values[i]);
+                r0 + theta * (r1 + theta1 * (r2 + theta * (r3 + theta1 * r4)))
+            }));
+        }
+    }
+
+    fn advance<F>(&mut self, t: &mut f64, y: &mut [Vec1<f64>], t_end: f64, f: &mut F) -> Result<(), SolverError>
+        where F: FnMut(f64, &[Vec1<f64>], &mut [Vec1<f64>])
+    {
+        if *t >= t_end {
+            return Ok(());
+        }
+
+        self.resize(y);
+
+        if !self.fsal {
+            f(*t, y, &mut self.k[0]);
+            self.stats.evaluations += 1;
+        }
+
+        if self.h <= 0.0 {
+            self.h = self.initial_step(*t, y, t_end, f);
+        }
+
+        let mut rejected = false;
+        loop {
+            let last = self.h >= t_end - *t;
+            let h = if last { t_end - *t } else { self.h };
+            if *t + h == *t {
+                return Err(SolverError::StepSizeTooSmall { t: *t });
+            }
+
+            let error = self.try_step(*t, h, y, f);
+            let factor = SAFETY * error.powf(-0.2);
+
+            if error <= 1.0 {
+                let max_factor = if rejected { 1.0 } else { MAX_FACTOR };
+                self.h = h * factor.clamp(MIN_FACTOR, max_factor);
+                let end = if last { t_end } else { *t + h };
+                self.update_dense(*t, end, h, y);
+
+                y.iter_mut()
+                    .zip(&self.y)
+                    .for_each(|(y, y1)| y.values.clone_from(&y1.values));
+                self.k.swap(0, 6);
+                self.fsal = true;
+                self.stats.accepted += 1;
+
+                *t = end;
+                return Ok(());
+            }
+
+            rejected = true;
+            self.stats.rejected += 1;
+            self.h = h * factor.max(MIN_FACTOR);
+        }
+    }
+
+    /// Evaluates stages two to seven from `k[0]`, leaving the fifth-order solution in `self.y`
+    /// and its derivative in `k[6]`, and returns the norm of the scaled error estimate.
+    fn try_step<F>(&mut self, t: f64, h: f64, y: &[Vec1<f64>], f: &mut F) -> f64
+        where F: FnMut(f64, &[Vec1<f64>], &mut [Vec1<f64>])
+    {
+        for stage in 1..7 {
+            let (k, rest) = self.k.split_at_mut(stage);
+
+            for (c, column) in self.y.iter_mut().enumerate() {
+                column.values.clone_from(&y[c].values);
+                for (k, a) in k.iter().zip(A[stage]) {
+                    if *a != 0.0 {
+                        *column += &k[c] * &(h * a);
+                    }
+                }
+            }
+
+            f(t + C[stage] * h, &self.y, &mut rest[0]);
+        }
+        self.stats.evaluations += 6;
+
+        let mut sum = 0.0;
+        let mut max = 0.0f64;
+        let mut count = 0;
+
+        for (c, y0) in y.iter().enumerate() {
+            for (i, (y0, y1)) in y0.iter().zip(self.y[c].iter()).enumerate() {
+                let error = h * (0..7).map(|j| E[j] * self.k[j][c].values[i]).sum::<f64>();
+                let ratio = error / (self.atol + self.rtol * y0.abs().max(y1.abs()));
+                sum += ratio * ratio;
+                max = max.max(ratio.abs());
+                count += 1;
+            }
+        }
+
+        if !sum.is_finite() {
+            return f64::INFINITY;
+        }
+
+        match self.norm {
+            ErrorNorm::Global => (sum / count.max(1) as f64).sqrt(),
+            ErrorNorm::PerElement => max,
+        }
+    }
+
+    /// Hairer's starting step size, from the sizes of `y`, `f(t, y)` and an estimate of `f'`.
+    fn initial_step<F>(&mut self, t: f64, y: &[Vec1<f64>], t_end: f64, f: &mut F) -> f64
+        where F: FnMut(f64, &[Vec1<f64>], &mut [Vec1<f64>])
+    {
+        let d0 = self.rms(y, |c, i| y[c].values[i]);
+        let d1 = self.rms(y, |c, i| self.k[0][c].values[i]);
+
+        let h0 = if d0 < 1e-5 || d1 < 1e-5 { 1e-6 } else { 0.01 * d0 / d1 };
+        let h0 = h0.min(t_end - t);
+
+        for (c, column) in self.y.iter_mut().enumerate() {
+            column.values.clone_from(&y[c].values);
+            *column += &self.k[0][c] * &h0;
+        }
+        f(t + h0, &self.y, &mut self.k[1]);
+        self.stats.evaluations += 1;
+
+        let d2 = self.rms(y, |c, i| self.k[1][c].values[i] - self.k[0][c].values[i]) / h0;
+        let h1 = if d1.max(d2) <= 1e-15 {
+            (h0 * 1e-3).max(1e-6)
+        } else {
+            (0.01 / d1.max(d2)).powf(0.2)
+        };
+
+        (100.0 * h0).min(h1)
+    }
+
+    /// The root mean square of `value(column, index)` scaled by the tolerances at `y`.
+    fn rms(&self, y: &[Vec1<f64>], value: impl Fn(usize, usize) -> f64) -> f64 {
+        let mut sum = 0.0;
+        let mut count = 0;
+
+        for (c, column) in y.iter().enumerate() {
+            for (i, y) in column.iter().enumerate() {
+                let ratio = value(c, i) / (self.atol + self.rtol * y.abs());
+                sum += ratio * ratio;
+                count += 1;
+            }
+        }
+
+        (sum / count.max(1) as f64).sqrt()
+    }
+
+    fn update_dense(&mut self, t: f64, end: f64, h: f64, y: &[Vec1<f64>]) {
+        let Dense { r, .. } = &mut self.dense;
+        let k = &self.k;
+
+        for (c, y0) in y.iter().enumerate() {
+            for (i, (&y0, &y1)) in y0.iter().zip(self.y[c].iter()).enumerate() {
+                let diff = y1 - y0;
+                let bspl = h * k[0][c].values[i] - diff;
+
+                r[0][c].values[i] = y0;
+                r[1][c].values[i] = diff;
+                r[2][c].values[i] = bspl;
+                r[3][c].values[i] = diff - h * k[6][c].values[i] - bspl;
+                r[4][c].values[i] = h * (0..7).map(|j| D[j] * k[j][c].values[i]).sum::<f64>();
+            }
+        }
+
+        self.dense.t = t;
+        self.dense.end = end;
+        self.dense.h = h;
+    }
+
+    /// Shapes every buffer like `y`.
+    fn resize(&mut self, y: &[Vec1<f64>]) {
+        let buffers = self.k.iter_mut()
+            .chain(std::iter::once(&mut self.y))
+            .chain(self.dense.r.iter_mut());
+
+        for buffer in buffers {
+            buffer.resize_with(y.len(), Vec1::default);
+            buffer.iter_mut()
+                .zip(y)
+                .for_each(|(buffer, y)| buffer.values.resize(y.len(), 0.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `x'' = -x` as the columns `[x, v]`.
+    fn oscillator(_t: f64, y: &[Vec1<f64>], dydt: &mut [Vec1<f64>]) {
+        dydt[0].values.clone_from(&y[1].values);
+        dydt[1].values.iter_mut()
+            .zip(y[0].iter())
+            .for_each(|(a, x)| *a = -x);
+    }
+
+    fn oscillator_start() -> [Vec1<f64>; 2] {
+        [vec![1.0, 0.0, 2.0].into(), vec![0.0, 1.0, 0.0].into()]
+    }
+
+    #[test]
+    fn exponential_decay() {
+        let mut solver = DormandPrince::new(1e-8, 1e-10);
+        let mut t = 0.0;
+        let mut y = [Vec1::from(vec![1.0, 2.0, -1.0])];
+
+        solver.solve(&mut t, &mut y, 2.0, |_, y, dydt| {
+            dydt[0].values.iter_mut()
+                .zip(y[0].iter())
+                .for_each(|(d, y)| *d = -y);
+        }).unwrap();
+
+        assert_eq!(2.0, t);
+        for (actual, start) in y[0].iter().zip(&[1.0, 2.0, -1.0]) {
+            assert!((actual - start * (-2.0f64).exp()).abs() < 1e-8);
+        }
+
+        let stats = solver.stats();
+        assert_eq!(1 + 6 * (stats.accepted + stats.rejected) + 1, stats.evaluations);
+    }
+
+    #[test]
+    fn oscillator_and_dense_output() {
+        let mut solver = DormandPrince::new(1e-10, 1e-12);
+        let mut t = 0.0;
+        let mut y = oscillator_start();
+        let exact = |t: f64| [(t.cos(), t.sin(), 2.0 * t.cos()), (-t.sin(), t.cos(), -2.0 * t.sin())];
+
+        let mut out = [Vec1::default(), Vec1::default()];
+        while t < 10.0 {
+            let t0 = t;
+            solver.step(&mut t, &mut y, 10.0, oscillator).unwrap();
+
+            let mid = 0.5 * (t0 + t);
+            solver.interpolate(mid, &mut out);
+            for (column, (a, b, c)) in out.iter().zip(&exact(mid)) {
+                assert!(column.iter().zip(&[*a, *b, *c]).all(|(x, e)| (x - e).abs() < 1e-7), "{} {:?}", mid, out);
+            }
+        }
+
+        solver.interpolate(10.0, &mut out);
+        assert_eq!(y, out);
+
+        for (column, (a, b, c)) in y.iter().zip(&exact(10.0)) {
+            assert!(column.iter().zip(&[*a, *b, *c]).all(|(x, e)| (x - e).abs() < 1e-8), "{:?}", y);
+        }
+    }
+
+    #[test]
+    fn per_element_norm_is_stricter() {
+        let solve = |norm| {
+            let mut solver = DormandPrince::new(1e-6, 1e-6);
+            solver.norm = norm;
+
+            let mut t = 0.0;
+            let mut y = [
+                Vec1::from(vec![1.0; 100]),
+                Vec1::from((0..100).map(|i| if i == 0 { 1.0 } else { 0.0 }).collect::<Vec<_>>()),
+            ];
+
+            solver.solve(&mut t, &mut y, 5.0, oscillator).unwrap();
+            solver.stats()
+        };
+
+        let global = solve(ErrorNorm::Global);
+        let per_element = solve(ErrorNorm::PerElement);
+
+        assert!(per_element.accepted > global.accepted, "{:?} {:?}", per_element, global);
+    }
+
+    #[test]
+    fn rejects_steps_on_a_stiff_problem() {
+        let mut solver = DormandPrince::new(1e-6, 1e-9);
+        let mut t = 0.0;
+        let mut y = [Vec1::from(vec![0.0])];
+
+        solver.solve(&mut t, &mut y, 3.0, |t, y, dydt| {
+            dydt[0].values[0] = -50.0 * (y[0].values[0] - t.cos());
+        }).unwrap();
+
+        let exact = 50.0 * (50.0 * 3.0f64.cos() + 3.0f64.sin()) / 2501.0 - 2500.0 / 2501.0 * (-150.0f64).exp();
+        assert!(solver.stats().rejected > 0);
+        assert!((y[0].values[0] - exact).abs() < 1e-5);
+    }
+
+    #[test]
+    fn errors() {
+        let mut solver = DormandPrince::new(1e-6, 1e-9);
+        solver.max_steps = 3;
+
+        let mut t = 0.0;
+        let mut y = oscillator_start();
+        let result = solver.solve(&mut t, &mut y, 10.0, oscillator);
+
+        assert_eq!(Err(SolverError::TooManySteps { t }), result);
+        assert_eq!(3, solver.stats().accepted);
+        assert!(t > 0.0 && t < 10.0);
+
+        let mut solver = DormandPrince::new(1e-6, 1e-9);
+        let mut t = 0.0;
+        let mut y = [Vec1::from(vec![1.0])];
+        let result = solver.solve(&mut t, &mut y, 1.0, |_, _, dydt| dydt[0].values[0] = f64::NAN);
+
+        assert_eq!(Err(SolverError::StepSizeTooSmall { t: 0.0 }), result);
+        assert_eq!("step size too small at t = 0", result.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn step_at_end_does_nothing() {
+        let mut solver = DormandPrince::new(1e-6, 1e-9);
+        let mut t = 1.0;
+        let mut y = oscillator_start();
+        let start = y.clone();
+
+        assert_eq!(Ok(()), solver.step(&mut t, &mut y, 1.0, |_, _, _| panic!("evaluated at the end")));
+        assert_eq!(Ok(()), solver.step(&mut t, &mut y, 0.5, |_, _, _| panic!("evaluated past the end")));
+        assert_eq!(Ok(()), solver.solve(&mut t, &mut y, 0.5, oscillator));
+
+        assert_eq!(1.0, t);
+        assert_eq!(start, y);
+        assert_eq!(Stats::default(), solver.stats());
+    }
+}
@@ -0,0 +1,385 @@
+//! Batch integrators for positions and velocities under an acceleration.
+//!
+//! Positions, velocities and accelerations are any `Columns` (`Vec1`, `Vec2`, `Vec3` or `VecN`)
+//! of the same dimension, with element types related through the time step, e.g.
+//! `Speed * Time = Length` and `Acceleration * Time = Speed`. The time step is any scalar or
+//! `Vec1` expression, so `&dt` gives a uniform step and `&Vec1<Time>` a step per element.
+//!
+//! ```
+//! # use simd_vecs::vecs::{Vec1, Vec2};
+//! # use simd_vecs::integrate;
+//! let mut position = Vec2 { x: Vec1::from(vec![0.0]), y: Vec1::from(vec![10.0]) };
+//! let mut velocity = Vec2 { x: Vec1::from(vec![1.0]), y: Vec1::from(vec![0.0]) };
+//! let gravity = Vec2 { x: Vec1::from(vec![0.0]), y: Vec1::from(vec![-10.0]) };
+//!
+//! integrate::semi_implicit_euler(&mut position, &mut velocity, &gravity, &0.5);
+//!
+//! assert_eq!(vec![0.5], position.x.values);
+//! assert_eq!(vec![7.5], position.y.values);
+//! ```
+//!
+//! `velocity_verlet` and `Rk4` split the step by multiplying it with fractions in the dimensionless
+//! type `Dt::Output / Dt::Output`, which is `f64` for `Time` and `f32` for `f32`.
+//!
+//! For first-order systems that need error control rather than fixed steps, see `DormandPrince`.
+
+use crate::ops::{Combine, Dim1, VExpr};
+use crate::vecs::Columns;
+use num_traits::FromPrimitive;
+use std::ops::{Add, Div, Mul};
+
+pub use dormand_prince::{DormandPrince, ErrorNorm, SolverError, Stats};
+
+mod dormand_prince;
+
+/// `x += v dt` then `v += a dt`, with the position advanced by the velocity from the start of the step.
+pub fn euler<P, V, A, Dt>(position: &mut P, velocity: &mut V, acceleration: &A, dt: Dt)
+    where
+        P: Columns,
+        V: Columns,
+        A: Columns,
+        P::Element: Copy + Add<Output=P::Element>,
+        V::Element: Copy + Add<Output=V::Element> + Mul<Dt::Output, Output=P::Element>,
+        A::Element: Copy + Mul<Dt::Output, Output=V::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy,
+        Dim1: Combine<Dt::Shape, Output=Dim1>,
+{
+    add_product(position, velocity, &dt);
+    add_product(velocity, acceleration, &dt);
+}
+
+/// `v += a dt` then `x += v dt`, which is symplectic and keeps orbits bounded where `euler` spirals out.
+pub fn semi_implicit_euler<P, V, A, Dt>(position: &mut P, velocity: &mut V, acceleration: &A, dt: Dt)
+    where
+        P: Columns,
+        V: Columns,
+        A: Columns,
+        P::Element: Copy + Add<Output=P::Element>,
+        V::Element: Copy + Add<Output=V::Element> + Mul<Dt::Output, Output=P::Element>,
+        A::Element: Copy + Mul<Dt::Output, Output=V::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy,
+        Dim1: Combine<Dt::Shape, Output=Dim1>,
+{
+    add_product(velocity, acceleration, &dt);
+    add_product(position, velocity, &dt);
+}
+
+/// Velocity Verlet in kick-drift-kick form. `acceleration` holds the accelerations at the start
+/// of the step, and `f` overwrites it with the accelerations at the new positions, ready for the next step.
+pub fn velocity_verlet<P, V, A, Dt, S, F>(position: &mut P, velocity: &mut V, acceleration: &mut A, dt: Dt, mut f: F)
+    where
+        P: Columns,
+        V: Columns,
+        A: Columns,
+        P::Element: Copy + Add<Output=P::Element>,
+        V::Element: Copy + Add<Output=V::Element> + Mul<Dt::Output, Output=P::Element>,
+        A::Element: Copy + Mul<Dt::Output, Output=V::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy + Div<Output=S> + Mul<S, Output=Dt::Output>,
+        S: Copy + FromPrimitive,
+        Dim1: Combine<Dt::Shape, Output=Dim1>,
+        F: FnMut(&P, &mut A),
+{
+    let half = S::from_f64(0.5).unwrap();
+
+    add_scaled(velocity, acceleration, &dt, half);
+    add_product(position, velocity, &dt);
+    f(position, acceleration);
+    add_scaled(velocity, acceleration, &dt, half);
+}
+
+/// Classic fourth-order Runge-Kutta for `x'' = f(x, v)`, which keeps its intermediate states
+/// between steps to avoid reallocating them.
+#[derive(Debug, Default, Clone)]
+pub struct Rk4<P, V, A> {
+    position: P,
+    velocity: [V; 3],
+    acceleration: [A; 4],
+}
+
+impl<P, V, A> Rk4<P, V, A>
+    where
+        P: Columns + Default,
+        V: Columns + Default,
+        A: Columns + Default,
+        P::Element: Copy + Add<Output=P::Element>,
+        V::Element: Copy + Add<Output=V::Element>,
+        A::Element: Copy + Default + Add<Output=A::Element>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances `position` and `velocity` by `dt`. `f` overwrites its `acceleration` argument, which
+    /// has the same length as the positions, with the accelerations at the given state.
+    pub fn step<Dt, S, F>(&mut self, position: &mut P, velocity: &mut V, dt: Dt, mut f: F)
+        where
+            V::Element: Mul<Dt::Output, Output=P::Element>,
+            A::Element: Mul<Dt::Output, Output=V::Element>,
+            Dt: VExpr,
+            Dt::Output: Copy + Div<Output=S> + Mul<S, Output=Dt::Output>,
+            S: Copy + FromPrimitive,
+            Dim1: Combine<Dt::Shape, Output=Dim1>,
+            F: FnMut(&P, &V, &mut A),
+    {
+        let half = S::from_f64(0.5).unwrap();
+        let sixth = S::from_f64(1.0 / 6.0).unwrap();
+        let len = len(position);
+        let [v2, v3, v4] = &mut self.velocity;
+        let [a1, a2, a3, a4] = &mut self.acceleration;
+
+        resize(a1, len);
+        f(position, velocity, a1);
+
+        copy(&mut self.position, position);
+        add_scaled(&mut self.position, velocity, &dt, half);
+        copy(v2, velocity);
+        add_scaled(v2, a1, &dt, half);
+        resize(a2, len);
+        f(&self.position, v2, a2);
+
+        copy(&mut self.position, position);
+        add_scaled(&mut self.position, v2, &dt, half);
+        copy(v3, velocity);
+        add_scaled(v3, a2, &dt, half);
+        resize(a3, len);
+        f(&self.position, v3, a3);
+
+        copy(&mut self.position, position);
+        add_product(&mut self.position, v3, &dt);
+        copy(v4, velocity);
+        add_product(v4, a3, &dt);
+        resize(a4, len);
+        f(&self.position, v4, a4);
+
+        add_weighted(position, [&*velocity, v2, v3, v4], &dt, sixth);
+        add_weighted(velocity, [&*a1, a2, a3, a4], &dt, sixth);
+    }
+}
+
+fn len<C: Columns>(vec: &C) -> usize {
+    if C::DIM == 0 { 0 } else { vec.column(0).len() }
+}
+
+fn copy<C: Columns>(target: &mut C, source: &C) where C::Element: Clone {
+    for axis in 0..C::DIM {
+        target.column_mut(axis).values.clone_from(&source.column(axis).values);
+    }
+}
+
+fn resize<C: Columns>(vec: &mut C, len: usize) where C::Element: Clone + Default {
+    for axis in 0..C::DIM {
+        vec.column_mut(axis).values.resize(len, Default::default());
+    }
+}
+
+fn check_dt<Dt: VExpr>(dt: &Dt, len: usize) {
+    if let Some(size) = dt.size() {
+        assert_len!(len, size);
+    }
+}
+
+/// `target += rate * dt`
+fn add_product<C, R, Dt>(target: &mut C, rate: &R, dt: &Dt)
+    where
+        C: Columns,
+        R: Columns,
+        C::Element: Copy + Add<Output=C::Element>,
+        R::Element: Copy + Mul<Dt::Output, Output=C::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy,
+{
+    add_rate(target, rate, dt, |dt| dt);
+}
+
+/// `target += rate * dt * factor`
+fn add_scaled<C, R, Dt, S>(target: &mut C, rate: &R, dt: &Dt, factor: S)
+    where
+        C: Columns,
+        R: Columns,
+        C::Element: Copy + Add<Output=C::Element>,
+        R::Element: Copy + Mul<Dt::Output, Output=C::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy + Mul<S, Output=Dt::Output>,
+        S: Copy,
+{
+    add_rate(target, rate, dt, |dt| dt * factor);
+}
+
+fn add_rate<C, R, Dt, F>(target: &mut C, rate: &R, dt: &Dt, step: F)
+    where
+        C: Columns,
+        R: Columns,
+        C::Element: Copy + Add<Output=C::Element>,
+        R::Element: Copy + Mul<Dt::Output, Output=C::Element>,
+        Dt: VExpr,
+        F: Fn(Dt::Output) -> Dt::Output,
+{
+    check_dt(dt, len(target));
+
+    for axis in 0..C::DIM.min(R::DIM) {
+        let (target, rate) = (target.column_mut(axis), rate.column(axis));
+        assert_len!(target.len(), rate.len());
+
+        target.values.iter_mut()
+            .zip(rate.iter())
+            .enumerate()
+            .for_each(|(i, (value, rate))| *value = *value + *rate * step(dt.get(i, 0)));
+    }
+}
+
+/// `target += (k1 + 2 k2 + 2 k3 + k4) * dt * sixth`
+fn add_weighted<C, R, Dt, S>(target: &mut C, [k1, k2, k3, k4]: [&R; 4], dt: &Dt, sixth: S)
+    where
+        C: Columns,
+        R: Columns,
+        C::Element: Copy + Add<Output=C::Element>,
+        R::Element: Copy + Add<Output=R::Element> + Mul<Dt::Output, Output=C::Element>,
+        Dt: VExpr,
+        Dt::Output: Copy + Mul<S, Output=Dt::Output>,
+        S: Copy,
+{
+    check_dt(dt, len(target));
+
+    for axis in 0..C::DIM.min(R::DIM) {
+        let target = target.column_mut(axis);
+        let [k1, k2, k3, k4] = [k1, k2, k3, k4].map(|k| k.column(axis));
+        assert_len!(target.len(), k4.len());
+
+        target.values.iter_mut()
+            .zip(k1.iter().zip(k2.iter()))
+            .zip(k3.iter().zip(k4.iter()))
+            .enumerate()
+            .for_each(|(i, ((value, (k1, k2)), (k3, k4)))| {
+                let rate = *k1 + *k2 + *k2 + *k3 + *k3 + *k4;
+                *value = *value + rate * (dt.get(i, 0) * sixth);
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vecs::{Vec1, Vec2};
+    use physics::*;
+
+    fn circle() -> (Vec2<f64>, Vec2<f64>) {
+        let position = Vec2 { x: vec![1.0, 2.0].into(), y: vec![0.0, 0.0].into() };
+        let velocity = Vec2 { x: vec![0.0, 0.0].into(), y: vec![1.0, 0.5f64.sqrt()].into() };
+        (position, velocity)
+    }
+
+    /// Inverse-square gravity towards the origin with `GM = 1`.
+    fn gravity(position: &Vec2<f64>, acceleration: &mut Vec2<f64>) {
+        acceleration.x.values.resize(position.len(), 0.0);
+        acceleration.y.values.resize(position.len(), 0.0);
+
+        for i in 0..position.len() {
+            let (x, y) = (position.x.values[i], position.y.values[i]);
+            let r3 = (x * x + y * y).powf(1.5);
+            acceleration.x.values[i] = -x / r3;
+            acceleration.y.values[i] = -y / r3;
+        }
+    }
+
+    fn radius_error(position: &Vec2<f64>) -> f64 {
+        let expected = [1.0, 2.0];
+        (0..2)
+            .map(|i| (position.x.values[i].hypot(position.y.values[i]) - expected[i]).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn circular_orbit_accuracy() {
+        let steps = 1000;
+        let dt = 0.01;
+        let mut errors = vec![];
+
+        let (mut position, mut velocity) = circle();
+        let mut acceleration = Vec2::default();
+        for _ in 0..steps {
+            gravity(&position, &mut acceleration);
+            euler(&mut position, &mut velocity, &acceleration, &dt);
+        }
+        errors.push(radius_error(&position));
+
+        let (mut position, mut velocity) = circle();
+        for _ in 0..steps {
+            gravity(&position, &mut acceleration);
+            semi_implicit_euler(&mut position, &mut velocity, &acceleration, &dt);
+        }
+        errors.push(radius_error(&position));
+
+        let (mut position, mut velocity) = circle();
+        gravity(&position, &mut acceleration);
+        for _ in 0..steps {
+            velocity_verlet(&mut position, &mut velocity, &mut acceleration, &dt, gravity);
+        }
+        errors.push(radius_error(&position));
+
+        let (mut position, mut velocity) = circle();
+        let mut rk4 = Rk4::new();
+        for _ in 0..steps {
+            rk4.step(&mut position, &mut velocity, &dt, |p, _, a| gravity(p, a));
+        }
+        errors.push(radius_error(&position));
+
+        assert!(errors[0] > 0.01, "{:?}", errors);
+        assert!(errors[1] < 0.01, "{:?}", errors);
+        assert!(errors[2] < 1e-4, "{:?}", errors);
+        assert!(errors[3] < 1e-8, "{:?}", errors);
+    }
+
+    #[test]
+    fn rk4_is_exact_for_constant_acceleration() {
+        let mut position = Vec1::from(vec![Length::in_meters(0.0), Length::in_meters(1.0)]);
+        let mut velocity = Vec1::from(vec![Speed::in_meters_per_second(2.0), Speed::in_meters_per_second(0.0)]);
+        let dt = Vec1::from(vec![Time::in_seconds(1.0), Time::in_seconds(2.0)]);
+
+        Rk4::new().step(&mut position, &mut velocity, &dt, |_, _, a: &mut Vec1<Acceleration>| {
+            a.values.iter_mut().for_each(|a| *a = Acceleration::in_meters_per_second_squared(-1.0));
+        });
+
+        assert_eq!(vec![Length::in_meters(1.5), Length::in_meters(-1.0)], position.values);
+        assert_eq!(vec![Speed::in_meters_per_second(1.0), Speed::in_meters_per_second(-2.0)], velocity.values);
+    }
+
+    #[test]
+    fn uniform_and_per_element_steps_agree() {
+        let dt = Time::in_seconds(0.5);
+        let steps = Vec1::from(vec![dt, dt]);
+        let start: Vec2<Length> = Vec2 { x: vec![0.0.into(), 1.0.into()].into(), y: vec![2.0.into(), 3.0.into()].into() };
+        let speed: Vec2<Speed> = Vec2 { x: vec![1.0.into(), 2.0.into()].into(), y: vec![(-1.0).into(), 0.0.into()].into() };
+        let acceleration: Vec2<Acceleration> = Vec2 { x: vec![2.0.into(), 0.0.into()].into(), y: vec![0.0.into(), 4.0.into()].into() };
+
+        let (mut p1, mut v1) = (start.clone(), speed.clone());
+        let (mut p2, mut v2) = (start, speed);
+        let (mut a1, mut a2) = (acceleration.clone(), acceleration.clone());
+
+        velocity_verlet(&mut p1, &mut v1, &mut a1, &dt, |_, _| {});
+        velocity_verlet(&mut p2, &mut v2, &mut a2, &steps, |_, _| {});
+
+        assert_eq!(p1, p2);
+        assert_eq!(v1, v2);
+        assert_eq!(Vec2 { x: vec![0.75.into(), 2.0.into()].into(), y: vec![1.5.into(), 3.5.into()].into() }, p1);
+        assert_eq!(Vec2 { x: vec![2.0.into(), 2.0.into()].into(), y: vec![(-1.0).into(), 2.0.into()].into() }, v1);
+    }
+
+    #[test]
+    fn f32_steps() {
+        let mut position = Vec1::from(vec![0.0f32, 1.0]);
+        let mut velocity = Vec1::from(vec![2.0f32, 0.0]);
+        let mut acceleration = Vec1::from(vec![-1.0f32, -1.0]);
+
+        velocity_verlet(&mut position, &mut velocity, &mut acceleration, &1.0f32, |_, _| {});
+        assert_eq!(vec![1.5, 0.5], position.values);
+
+        Rk4::new().step(&mut position, &mut velocity, &1.0f32, |_, _, a: &mut Vec1<f32>| {
+            a.values.iter_mut().for_each(|a| *a = -1.0);
+        });
+        assert_eq!(vec![2.0, -1.0], position.values);
+        assert_eq!(vec![0.0, -2.0], velocity.values);
+    }
+}
@@ -0,0 +1,270 @@
+//! Batch solutions of Kepler's equation, `M = E - e sin E`, for bodies on elliptical orbits.
+//!
+//! Every element is iterated in lockstep so that the sines and cosines of each iteration use the
+//! SIMD kernels over whole columns.
+
+use crate::vecs::{Vec1, Vec2};
+use crate::zip::Zip;
+use num_traits::Float;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Method {
+    /// Quadratic convergence, one division per iteration.
+    Newton,
+    /// Cubic convergence, which usually saves an iteration or two at high eccentricity.
+    Halley,
+}
+
+/// The outcome of `KeplerSolver::solve`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Convergence {
+    pub iterations: usize,
+    /// The number of elements whose last correction was larger than the tolerance.
+    pub unconverged: usize,
+}
+
+impl Convergence {
+    pub fn converged(&self) -> bool {
+        self.unconverged == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeplerSolver<T> {
+    pub method: Method,
+    /// Iteration stops once every correction to the eccentric anomaly is at most this many radians.
+    pub tolerance: T,
+    pub max_iterations: usize,
+}
+
+impl<T: Float> Default for KeplerSolver<T> {
+    fn default() -> Self {
+        KeplerSolver {
+            method: Method::Halley,
+            tolerance: T::epsilon() * T::from(64.0).unwrap(),
+            max_iterations: 16,
+        }
+    }
+}
+
+impl<T: Float + Send + Sync + 'static> KeplerSolver<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the eccentric anomaly of each element into `eccentric_anomaly`, for mean anomalies in
+    /// radians and eccentricities in `[0, 1)`. The results lie within about `e` of the mean anomaly
+    /// wrapped into (-pi, pi].
+    pub fn solve(&self, mean_anomaly: &Vec1<T>, eccentricity: &Vec1<T>, eccentric_anomaly: &mut Vec1<T>) -> Convergence {
+        assert_len!(mean_anomaly.len(), eccentricity.len());
+
+        let mut mean_anomaly = mean_anomaly.clone();
+        mean_anomaly.wrap_angles();
+
+        // Danby's starting value, E = M + 0.85 e sign(sin M), converges for every e < 1
+        let k = T::from(0.85).unwrap();
+        let sin = mean_anomaly.sin();
+        eccentric_anomaly.values.clone_from(&mean_anomaly.values);
+        Zip::new(&mut *eccentric_anomaly)
+            .and(&sin)
+            .and(eccentricity)
+            .for_each(|anomaly, sin, e| *anomaly = *anomaly + k * e * sin.signum());
+
+        let half = T::from(0.5).unwrap();
+        let mut convergence = Convergence {
+            iterations: 0,
+            unconverged: mean_anomaly.len(),
+        };
+
+        while convergence.unconverged > 0 && convergence.iterations < self.max_iterations {
+            let (sin, cos) = eccentric_anomaly.sin_cos();
+            let mut unconverged = 0;
+
+            Zip::new(&mut *eccentric_anomaly)
+                .and(&sin)
+                .and(&cos)
+                .and(eccentricity)
+                .and(&mean_anomaly)
+                .for_each(|anomaly, sin, cos, e, mean| {
+                    let f = *anomaly - e * sin - mean;
+                    let df = T::one() - e * cos;
+
+                    let step = match self.method {
+                        Method::Newton => f / df,
+                        Method::Halley => f / (df - half * f * e * sin / df),
+                    };
+
+                    *anomaly = *anomaly - step;
+                    if step.is_nan() || step.abs() > self.tolerance {
+                        unconverged += 1;
+                    }
+                });
+
+            convergence.iterations += 1;
+            convergence.unconverged = unconverged;
+        }
+
+        convergence
+    }
+}
+
+/// Keplerian elements of bodies orbiting in a plane, with angles in radians.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Elements<L, T> {
+    pub semi_major_axis: Vec1<L>,
+    pub eccentricity: Vec1<T>,
+    pub argument_of_periapsis: Vec1<T>,
+    pub mean_anomaly: Vec1<T>,
+}
+
+impl<L, T> Elements<L, T>
+    where
+        L: Copy + Default + Add<Output=L> + Sub<Output=L> + Mul<T, Output=L>,
+        T: Float + Send + Sync + 'static,
+{
+    pub fn len(&self) -> usize {
+        self.semi_major_axis.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Solves Kepler's equation for every body and writes its position relative to the focus into `target`.
+    pub fn positions(&self, solver: &KeplerSolver<T>, target: &mut Vec2<L>) -> Convergence {
+        let mut eccentric_anomaly = Vec1::default();
+        let convergence = solver.solve(&self.mean_anomaly, &self.eccentricity, &mut eccentric_anomaly);
+        positions(&self.semi_major_axis, &self.eccentricity, &self.argument_of_periapsis, &eccentric_anomaly, target);
+        convergence
+    }
+}
+
+/// Writes the positions relative to the focus, with periapsis in the direction of the argument of periapsis.
+pub fn positions<L, T>(
+    semi_major_axis: &Vec1<L>,
+    eccentricity: &Vec1<T>,
+    argument_of_periapsis: &Vec1<T>,
+    eccentric_anomaly: &Vec1<T>,
+    target: &mut Vec2<L>,
+)
+    where
+        L: Copy + Default + Add<Output=L> + Sub<Output=L> + Mul<T, Output=L>,
+        T: Float + Send + Sync + 'static,
+{
+    let len = semi_major_axis.len();
+    assert_len!(len, eccentricity.len());
+    assert_len!(len, argument_of_periapsis.len());
+    assert_len!(len, eccentric_anomaly.len());
+
+    let (sin, cos) = eccentric_anomaly.sin_cos();
+    let (sin_w, cos_w) = argument_of_periapsis.sin_cos();
+
+    target.x.values.resize(len, L::default());
+    target.y.values.resize(len, L::default());
+
+    Zip::new(target)
+        .and(semi_major_axis)
+        .and(eccentricity)
+        .and(&sin)
+        .and(&cos)
+        .and(&sin_w)
+        .and(&cos_w)
+        .for_each(|(x, y), a, e, sin, cos, sin_w, cos_w| {
+            let px = a * (cos - e);
+            let py = a * ((T::one() - e * e).sqrt() * sin);
+            *x = px * cos_w - py * sin_w;
+            *y = px * sin_w + py * cos_w;
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use physics::Length;
+
+    fn grid() -> (Vec1<f64>, Vec1<f64>) {
+        let mut mean_anomaly = vec![];
+        let mut eccentricity = vec![];
+
+        for i in 0..=40 {
+            for &e in &[0.0, 0.1, 0.5, 0.9, 0.99, 0.999] {
+                mean_anomaly.push(-7.0 + 0.35 * i as f64);
+                eccentricity.push(e);
+            }
+        }
+
+        (mean_anomaly.into(), eccentricity.into())
+    }
+
+    fn max_residual(mean_anomaly: &Vec1<f64>, eccentricity: &Vec1<f64>, eccentric_anomaly: &Vec1<f64>) -> f64 {
+        let mut max = 0.0f64;
+        Zip::new(mean_anomaly)
+            .and(eccentricity)
+            .and(eccentric_anomaly)
+            .for_each(|m, e, anomaly| {
+                let residual = (anomaly - e * anomaly.sin()).sin_cos();
+                let (sin, cos) = m.sin_cos();
+                max = max.max((residual.0 - sin).abs() + (residual.1 - cos).abs());
+            });
+        max
+    }
+
+    #[test]
+    fn newton_and_halley_converge() {
+        let (mean_anomaly, eccentricity) = grid();
+        let mut anomaly = Vec1::default();
+
+        let mut solver = KeplerSolver::new();
+        let halley = solver.solve(&mean_anomaly, &eccentricity, &mut anomaly);
+        assert!(halley.converged(), "{:?}", halley);
+        assert!(max_residual(&mean_anomaly, &eccentricity, &anomaly) < 1e-12);
+
+        solver.method = Method::Newton;
+        let newton = solver.solve(&mean_anomaly, &eccentricity, &mut anomaly);
+        assert!(newton.converged(), "{:?}", newton);
+        assert!(max_residual(&mean_anomaly, &eccentricity, &anomaly) < 1e-12);
+
+        assert!(halley.iterations < newton.iterations, "{:?} {:?}", halley, newton);
+    }
+
+    #[test]
+    fn iteration_limit_and_f32() {
+        let (mean_anomaly, eccentricity) = grid();
+        let mut anomaly = Vec1::default();
+
+        let solver = KeplerSolver { max_iterations: 1, ..KeplerSolver::new() };
+        let convergence = solver.solve(&mean_anomaly, &eccentricity, &mut anomaly);
+        assert_eq!(1, convergence.iterations);
+        assert!(!convergence.converged());
+
+        let mean_anomaly = Vec1::from(vec![0.5f32, 2.0, -3.0]);
+        let eccentricity = Vec1::from(vec![0.2f32, 0.7, 0.95]);
+        let mut anomaly = Vec1::default();
+        assert!(KeplerSolver::new().solve(&mean_anomaly, &eccentricity, &mut anomaly).converged());
+
+        for i in 0..3 {
+            let (m, e, anomaly) = (mean_anomaly.values[i], eccentricity.values[i], anomaly.values[i]);
+            assert!((anomaly - e * anomaly.sin() - m).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn positions_with_units() {
+        let elements = Elements {
+            semi_major_axis: Vec1::from(vec![Length::in_meters(2.0), Length::in_meters(1.0), Length::in_meters(4.0)]),
+            eccentricity: vec![0.5, 0.0, 0.5].into(),
+            argument_of_periapsis: vec![0.0, 0.0, std::f64::consts::FRAC_PI_2].into(),
+            mean_anomaly: vec![0.0, std::f64::consts::FRAC_PI_2, std::f64::consts::PI].into(),
+        };
+
+        let mut position = Vec2::default();
+        assert!(elements.positions(&KeplerSolver::new(), &mut position).converged());
+
+        let expected = [(1.0, 0.0), (0.0, 1.0), (0.0, -6.0)];
+        for (i, (x, y)) in expected.iter().enumerate() {
+            assert!((position.x.values[i].value - x).abs() < 1e-12, "{:?}", position);
+            assert!((position.y.values[i].value - y).abs() < 1e-12, "{:?}", position);
+        }
+    }
+}
@@ -1,2 +1,28 @@
+/// Asserts that two lengths match: always with the `checked` feature, otherwise only in debug builds.
+macro_rules! assert_len {
+    ($expected:expr, $found:expr) => {
+        if cfg!(feature = "checked") {
+            assert_eq!($expected, $found, "length mismatch");
+        } else {
+            debug_assert_eq!($expected, $found, "length mismatch");
+        }
+    };
+}
+
+pub mod error;
+pub mod id;
+pub mod integrate;
+pub mod kepler;
+pub mod nbody;
 pub mod vecs;
-pub mod ops;
\ No newline at end of file
+pub mod ops;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod zip;
+mod simd;
+mod sum;
+
+pub use error::SimdVecError;
+
+#[cfg(feature = "derive")]
+pub use simd_vecs_derive::Soa;
@@ -0,0 +1,423 @@
+//! Gravitational accelerations between bodies in a plane.
+//!
+//! Masses are gravitational parameters `G m`, so any consistent units work. A softening length
+//! `eps` replaces `1 / r^2` with `r / (r^2 + eps^2)^(3/2)` to keep close encounters finite, and a
+//! body feels no pull from itself, nor from another body at exactly its position.
+
+use crate::simd;
+use crate::vecs::{Vec1, Vec2};
+use num_traits::Float;
+
+/// Bodies per target tile, each of which is one task when running in parallel.
+const TILE: usize = 256;
+/// Cells with at most this many bodies are summed directly rather than subdivided.
+const LEAF_SIZE: usize = 8;
+/// Stops subdividing bodies that share a position.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Method<T> {
+    /// Every pair in O(n^2), using tiled SIMD kernels for `f32` and `f64`.
+    Direct,
+    /// A quadtree in O(n log n). A cell acts as a single body at its centre of mass once its width is
+    /// below `opening_angle` times the target's distance from it, less the offset between its centre of
+    /// mass and its geometric centre. An opening angle of zero gives the full sum.
+    BarnesHut { opening_angle: T },
+}
+
+/// Accumulates gravitational accelerations, keeping the Barnes-Hut quadtree between calls to reuse its allocations.
+#[derive(Debug, Clone)]
+pub struct Gravity<T> {
+    pub method: Method<T>,
+    pub softening: T,
+    tree: QuadTree<T>,
+}
+
+impl<T: Float + Send + Sync + 'static> Gravity<T> {
+    pub fn direct(softening: T) -> Self {
+        Self::new(Method::Direct, softening)
+    }
+
+    pub fn barnes_hut(opening_angle: T, softening: T) -> Self {
+        Self::new(Method::BarnesHut { opening_angle }, softening)
+    }
+
+    pub fn new(method: Method<T>, softening: T) -> Self {
+        Gravity {
+            method,
+            softening,
+            tree: QuadTree::default(),
+        }
+    }
+
+    /// Adds the pull of every body to `acceleration`, which must have the same length as `position`.
+    pub fn accumulate(&mut self, position: &Vec2<T>, mass: &Vec1<T>, acceleration: &mut Vec2<T>) {
+        let len = position.x.len();
+        assert_len!(len, position.y.len());
+        assert_len!(len, mass.len());
+        assert_len!(len, acceleration.x.len());
+        assert_len!(len, acceleration.y.len());
+
+        let softening2 = self.softening * self.softening;
+
+        match self.method {
+            Method::Direct => {
+                if !simd::gravity(acceleration, position, mass, &softening2) {
+                    direct(acceleration, position, mass, softening2);
+                }
+            }
+            Method::BarnesHut { opening_angle } => {
+                let bodies = [position.x.as_slice(), position.y.as_slice(), mass.as_slice()];
+                let inverse_angle = opening_angle.recip();
+                self.tree.build(bodies);
+
+                let tree = &self.tree;
+                simd::tiles(acceleration.x.as_mut_slice(), acceleration.y.as_mut_slice(), TILE, |start, ax, ay| {
+                    let mut stack = Vec::new();
+
+                    for (i, (ax, ay)) in ax.iter_mut().zip(ay).enumerate() {
+                        let (x, y) = tree.pull(bodies, start + i, inverse_angle, softening2, &mut stack);
+                        *ax = *ax + x;
+                        *ay = *ay + y;
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// `a += m d / (|d|^2 + softening2)^(3/2)`, clamped to zero for a source at the target's position.
+fn pull<T: Float>(acceleration: (&mut T, &mut T), dx: T, dy: T, mass: T, softening2: T) {
+    let r2 = dx * dx + dy * dy + softening2;
+    let s = (mass / (r2 * r2.sqrt())).min(T::max_value());
+    *acceleration.0 = *acceleration.0 + dx * s;
+    *acceleration.1 = *acceleration.1 + dy * s;
+}
+
+/// The direct sum for float types without SIMD kernels.
+fn direct<T: Float>(acceleration: &mut Vec2<T>, position: &Vec2<T>, mass: &Vec1<T>, softening2: T) {
+    let (x, y, m) = (position.x.as_slice(), position.y.as_slice(), mass.as_slice());
+
+    for (i, (ax, ay)) in acceleration.x.iter_mut().zip(acceleration.y.iter_mut()).enumerate() {
+        for j in 0..x.len() {
+            pull((&mut *ax, &mut *ay), x[j] - x[i], y[j] - y[i], m[j], softening2);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Node<T> {
+    /// Centre of mass
+    x: T,
+    y: T,
+    mass: T,
+    /// Width of the square cell
+    size: T,
+    /// Distance from the centre of mass to the centre of the cell
+    offset: T,
+    /// The bodies in `order[start..end]`
+    start: usize,
+    end: usize,
+    /// The nonempty quadrants in `nodes[child..child + children]`, none for a leaf
+    child: usize,
+    children: usize,
+}
+
+impl<T: Float> Node<T> {
+    fn new(size: T, start: usize, end: usize) -> Self {
+        Node {
+            x: T::zero(),
+            y: T::zero(),
+            mass: T::zero(),
+            size,
+            offset: T::zero(),
+            start,
+            end,
+            child: 0,
+            children: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QuadTree<T> {
+    nodes: Vec<Node<T>>,
+    /// Body indices, ordered so that every cell covers a contiguous range
+    order: Vec<usize>,
+}
+
+impl<T> Default for QuadTree<T> {
+    fn default() -> Self {
+        QuadTree {
+            nodes: vec![],
+            order: vec![],
+        }
+    }
+}
+
+impl<T: Float> QuadTree<T> {
+    fn build(&mut self, bodies: [&[T]; 3]) {
+        let [x, y, _] = bodies;
+        let len = x.len();
+
+        self.nodes.clear();
+        self.order.clear();
+        self.order.extend(0..len);
+
+        if len == 0 {
+            return;
+        }
+
+        let (min_x, max_x) = bounds(x);
+        let (min_y, max_y) = bounds(y);
+        let two = T::one() + T::one();
+
+        self.nodes.push(Node::new((max_x - min_x).max(max_y - min_y), 0, len));
+        self.subdivide(0, bodies, ((min_x + max_x) / two, (min_y + max_y) / two), 0);
+    }
+
+    /// Splits the cell at `index` into quadrants around `centre`, then sets its centre of mass.
+    fn subdivide(&mut self, index: usize, bodies: [&[T]; 3], (cx, cy): (T, T), depth: usize) {
+        let [x, y, mass] = bodies;
+        let mut node = self.nodes[index];
+
+        if node.end - node.start > LEAF_SIZE && depth < MAX_DEPTH {
+            let order = &mut self.order[node.start..node.end];
+            let upper = partition(order, |i| y[i] < cy);
+            let (lower, higher) = order.split_at_mut(upper);
+            let lower_left = partition(lower, |i| x[i] < cx);
+            let upper_left = upper + partition(higher, |i| x[i] < cx);
+
+            let splits = [0, lower_left, upper, upper_left, node.end - node.start].map(|i| node.start + i);
+            let quarter = node.size / T::from(4).unwrap();
+            let centres = [(cx - quarter, cy - quarter), (cx + quarter, cy - quarter), (cx - quarter, cy + quarter), (cx + quarter, cy + quarter)];
+
+            node.child = self.nodes.len();
+            for q in 0..4 {
+                if splits[q] < splits[q + 1] {
+                    self.nodes.push(Node::new(node.size / (T::one() + T::one()), splits[q], splits[q + 1]));
+                }
+            }
+            node.children = self.nodes.len() - node.child;
+
+            let mut child = node.child;
+            for q in 0..4 {
+                if splits[q] < splits[q + 1] {
+                    self.subdivide(child, bodies, centres[q], depth + 1);
+                    child += 1;
+                }
+            }
+
+            for child in &self.nodes[node.child..node.child + node.children] {
+                node.mass = node.mass + child.mass;
+                node.x = node.x + child.mass * child.x;
+                node.y = node.y + child.mass * child.y;
+            }
+        } else {
+            for &i in &self.order[node.start..node.end] {
+                node.mass = node.mass + mass[i];
+                node.x = node.x + mass[i] * x[i];
+                node.y = node.y + mass[i] * y[i];
+            }
+        }
+
+        if node.mass > T::zero() {
+            node.x = node.x / node.mass;
+            node.y = node.y / node.mass;
+            node.offset = (node.x - cx).hypot(node.y - cy);
+        } else {
+            node.x = cx;
+            node.y = cy;
+        }
+
+        self.nodes[index] = node;
+    }
+
+    /// The acceleration of body `i`, walking the tree with `stack`.
+    fn pull(&self, bodies: [&[T]; 3], i: usize, inverse_angle: T, softening2: T, stack: &mut Vec<usize>) -> (T, T) {
+        let [x, y, mass] = bodies;
+        let (xi, yi) = (x[i], y[i]);
+        let (mut ax, mut ay) = (T::zero(), T::zero());
+
+        stack.clear();
+        stack.push(0);
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            if node.children == 0 {
+                for &j in &self.order[node.start..node.end] {
+                    pull((&mut ax, &mut ay), x[j] - xi, y[j] - yi, mass[j], softening2);
+                }
+                continue;
+            }
+
+            let (dx, dy) = (node.x - xi, node.y - yi);
+            let limit = node.size * inverse_angle + node.offset;
+            if limit * limit < dx * dx + dy * dy {
+                pull((&mut ax, &mut ay), dx, dy, node.mass, softening2);
+            } else {
+                stack.extend(node.child..node.child + node.children);
+            }
+        }
+
+        (ax, ay)
+    }
+}
+
+fn bounds<T: Float>(values: &[T]) -> (T, T) {
+    values.iter().fold((T::infinity(), T::neg_infinity()), |(min, max), &v| (min.min(v), max.max(v)))
+}
+
+/// Moves the indices that satisfy `f` to the front, returning how many there are.
+fn partition<F: Fn(usize) -> bool>(order: &mut [usize], f: F) -> usize {
+    let mut split = 0;
+    for i in 0..order.len() {
+        if f(order[i]) {
+            order.swap(split, i);
+            split += 1;
+        }
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrate;
+
+    /// A scattered disc with a dense clump, including a few bodies on top of each other.
+    fn bodies(len: usize) -> (Vec2<f64>, Vec1<f64>) {
+        let mut position = Vec2::default();
+        let mut mass = Vec1::default();
+
+        for i in 0..len {
+            let t = i as f64;
+            let (r, angle) = if i % 5 == 0 { (0.01 * (t * 0.37).sin(), t) } else { (1.0 + (t * 1.3).sin(), t * 2.4) };
+            let (x, y) = if i % 97 == 1 { (0.5, 0.5) } else { (r * angle.cos(), r * angle.sin()) };
+            position.x.values.push(x);
+            position.y.values.push(y);
+            mass.values.push(1.0 + 0.5 * (t * 0.7).cos());
+        }
+
+        (position, mass)
+    }
+
+    fn pairwise(position: &Vec2<f64>, mass: &Vec1<f64>, softening: f64) -> Vec2<f64> {
+        let mut acceleration = Vec2::default_with_len(position.len());
+
+        for i in 0..position.len() {
+            for j in (0..position.len()).filter(|&j| j != i) {
+                let dx = position.x.values[j] - position.x.values[i];
+                let dy = position.y.values[j] - position.y.values[i];
+                let r2 = dx * dx + dy * dy + softening * softening;
+                if r2 > 0.0 {
+                    acceleration.x.values[i] += mass.values[j] * dx / r2.powf(1.5);
+                    acceleration.y.values[i] += mass.values[j] * dy / r2.powf(1.5);
+                }
+            }
+        }
+
+        acceleration
+    }
+
+    /// The RMS error relative to the RMS magnitude of `expected`.
+    fn relative_error(expected: &Vec2<f64>, actual: &Vec2<f64>) -> f64 {
+        let (mut error, mut norm) = (0.0, 0.0);
+        for i in 0..expected.len() {
+            let (x, y) = (expected.x.values[i], expected.y.values[i]);
+            error += (actual.x.values[i] - x).powi(2) + (actual.y.values[i] - y).powi(2);
+            norm += x * x + y * y;
+        }
+        (error / norm).sqrt()
+    }
+
+    #[test]
+    fn direct_matches_pairwise_sum() {
+        for &len in &[0, 1, 2, 37, 700] {
+            for &softening in &[0.0, 0.05] {
+                let (position, mass) = bodies(len);
+                let mut acceleration = Vec2::default_with_len(len);
+                Gravity::direct(softening).accumulate(&position, &mass, &mut acceleration);
+
+                let expected = pairwise(&position, &mass, softening);
+                assert!(acceleration.x.iter().chain(acceleration.y.iter()).all(|a| a.is_finite()));
+                if len > 1 {
+                    assert!(relative_error(&expected, &acceleration) < 1e-12, "{} {}", len, softening);
+                }
+            }
+        }
+
+        let position = Vec2 { x: vec![0.0f32, 3.0, 0.0].into(), y: vec![0.0f32, 0.0, 4.0].into() };
+        let mass = Vec1::from(vec![2.0f32, 9.0, 16.0]);
+        let mut acceleration = Vec2 { x: vec![1.0; 3].into(), y: vec![0.0; 3].into() };
+        Gravity::direct(0.0).accumulate(&position, &mass, &mut acceleration);
+
+        assert_eq!((2.0, 1.0), (acceleration.x.values[0], acceleration.y.values[0]));
+    }
+
+    #[test]
+    fn softening() {
+        let position = Vec2 { x: vec![0.0, 2.0, 2.0].into(), y: vec![0.0; 3].into() };
+        let mass = Vec1::from(vec![3.0, 1.0, 1.0]);
+
+        for gravity in &mut [Gravity::direct(1.0), Gravity::barnes_hut(0.5, 1.0)] {
+            let mut acceleration = Vec2::default_with_len(3);
+            gravity.accumulate(&position, &mass, &mut acceleration);
+
+            assert!((acceleration.x.values[0] - 4.0 / 5f64.powf(1.5)).abs() < 1e-15);
+            assert!((acceleration.x.values[1] + 6.0 / 5f64.powf(1.5)).abs() < 1e-15);
+            assert_eq!(vec![0.0; 3], acceleration.y.values);
+
+            gravity.softening = 0.0;
+            let mut acceleration = Vec2::default_with_len(3);
+            gravity.accumulate(&position, &mass, &mut acceleration);
+
+            assert_eq!(vec![0.5, -0.75, -0.75], acceleration.x.values);
+        }
+    }
+
+    #[test]
+    fn barnes_hut_approximates_direct() {
+        let (position, mass) = bodies(3000);
+        let mut expected = Vec2::default_with_len(3000);
+        Gravity::direct(0.01).accumulate(&position, &mass, &mut expected);
+
+        let mut gravity = Gravity::barnes_hut(0.0, 0.01);
+        let mut errors = vec![];
+        for &opening_angle in &[0.0, 0.3, 0.7] {
+            gravity.method = Method::BarnesHut { opening_angle };
+            let mut acceleration = Vec2::default_with_len(3000);
+            gravity.accumulate(&position, &mass, &mut acceleration);
+            errors.push(relative_error(&expected, &acceleration));
+        }
+
+        assert!(errors[0] < 1e-12, "{:?}", errors);
+        assert!(errors[1] < 5e-3, "{:?}", errors);
+        assert!(errors[1] < errors[2] && errors[2] < 3e-2, "{:?}", errors);
+    }
+
+    #[test]
+    fn binary_orbit_with_velocity_verlet() {
+        // equal masses a distance 2 apart on a circular orbit about their common centre
+        let mut position = Vec2 { x: vec![-1.0, 1.0].into(), y: vec![0.0, 0.0].into() };
+        let mut velocity = Vec2 { x: vec![0.0, 0.0].into(), y: vec![-0.5, 0.5].into() };
+        let mass = Vec1::from(vec![1.0, 1.0]);
+
+        let mut gravity = Gravity::barnes_hut(0.5, 0.0);
+        let mut acceleration = Vec2::default_with_len(2);
+        gravity.accumulate(&position, &mass, &mut acceleration);
+
+        let dt = 0.01;
+        for _ in 0..(4.0 * std::f64::consts::PI / dt) as usize {
+            integrate::velocity_verlet(&mut position, &mut velocity, &mut acceleration, &dt, |position, acceleration| {
+                *acceleration = Vec2::default_with_len(2);
+                gravity.accumulate(position, &mass, acceleration);
+            });
+        }
+
+        let separation = (position.x.values[1] - position.x.values[0]).hypot(position.y.values[1] - position.y.values[0]);
+        assert!((separation - 2.0).abs() < 1e-4, "{}", separation);
+        assert!((position.x.values[1] - 1.0).abs() < 1e-2, "{:?}", position);
+    }
+}
@@ -1,29 +1,485 @@
+use crate::error::{check_len, SimdVecError};
+use crate::vecs::{Vec1, Vec2, Vec3, VecN};
+use num_traits::Float;
+use std::ops::*;
+
+/// A lazily evaluated, element-wise expression over `Vec1`, `Vec2` and `Vec3` operands.
+///
+/// Expressions are built with the arithmetic operators on vector references and are
+/// evaluated in a single pass when assigned to a vector (e.g. `p += &v * &dt + &a * &dt2`).
+/// References to `Copy` scalars are broadcast across every element.
+pub trait VExpr {
+    type Output: Copy;
+    type Shape: Shape;
+
+    /// The number of elements, or `None` for a scalar broadcast.
+    fn size(&self) -> Option<usize>;
+
+    fn get(&self, index: usize, axis: usize) -> Self::Output;
+
+    /// The number of elements, checking that all operands agree.
+    fn try_size(&self) -> Result<Option<usize>, SimdVecError> {
+        Ok(self.size())
+    }
+
+    /// The backing column of a vector leaf, used to select the SIMD kernels.
+    fn column(&self, _axis: usize) -> Option<&Vec1<Self::Output>> {
+        None
+    }
+
+    /// The broadcast value of a scalar leaf.
+    fn value(&self) -> Option<Self::Output> {
+        None
+    }
+
+    /// Evaluates the expression into a newly allocated vector of its shape.
+    fn eval(&self) -> <Self::Shape as Shape>::Vec<Self::Output> where Self: Sized {
+        Self::Shape::eval(self)
+    }
+}
+
+/// Numbers that multiply or divide every element of a column, e.g. `v *= 2.0` on a
+/// `Vec2<Length>`. Implemented for the primitive numbers; other types, such as a dimensionless
+/// newtype, can implement it to be used the same way. Adding or subtracting a single value takes
+/// the element type itself, e.g. `Vec1<Length> += Length`, and needs no `Scalar` impl.
+pub trait Scalar: Copy {}
+
+macro_rules! scalar {
+    ($($t:ty),+) => {
+        $(impl Scalar for $t {})+
+    };
+}
+
+scalar!(f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Element types with a length: the product `Squared` of two elements, such as `Length * Length = Area`,
+/// and its square root. Implemented for every type whose ratio to itself is a `Float` and that can be
+/// built from that ratio, e.g. `f64`, or unit types with `Area / Area = f64` and `Length: From<f64>`.
+pub trait Norm: Copy + Mul<Self, Output=<Self as Norm>::Squared> {
+    type Squared: Copy + Add<Output=Self::Squared>;
+
+    fn root(squared: Self::Squared) -> Self;
+}
+
+impl<T, S, R> Norm for T
+    where
+        T: Copy + Mul<T, Output=S> + Mul<R, Output=T> + From<R>,
+        S: Copy + Add<Output=S> + Div<S, Output=R>,
+        R: Float,
+{
+    type Squared = S;
+
+    /// Taken as `unit * sqrt(squared / unit^2)`, where the unit cancels out.
+    fn root(squared: S) -> T {
+        let unit = T::from(R::one());
+        unit * (squared / (unit * unit)).sqrt()
+    }
+}
+
+pub trait Shape: Sized {
+    type Vec<T>;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> Self::Vec<E::Output>;
+}
+
+/// The shape of an expression combining two operands of shapes `Self` and `Rhs`.
+pub trait Combine<Rhs: Shape>: Shape {
+    type Output: Shape;
+}
+
 #[derive(Debug, Copy, Clone)]
-pub struct VMul<'a, A, B> {
-    pub a: &'a A,
-    pub b: &'a B,
+pub struct Dim0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Dim1;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Dim2;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Dim3;
+
+/// The shape of `VecN<T, N>` operands.
+#[derive(Debug, Copy, Clone)]
+pub struct DimN<const N: usize>;
+
+fn collect_axis<E: VExpr>(expr: &E, axis: usize) -> Vec1<E::Output> {
+    let len = expr.size().unwrap_or(0);
+    Vec1::from((0..len).map(|i| expr.get(i, axis)).collect::<Vec<_>>())
+}
+
+impl Shape for Dim0 {
+    type Vec<T> = T;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> E::Output {
+        expr.get(0, 0)
+    }
+}
+
+impl Shape for Dim1 {
+    type Vec<T> = Vec1<T>;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> Vec1<E::Output> {
+        collect_axis(expr, 0)
+    }
+}
+
+impl Shape for Dim2 {
+    type Vec<T> = Vec2<T>;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> Vec2<E::Output> {
+        Vec2 {
+            x: collect_axis(expr, 0),
+            y: collect_axis(expr, 1),
+        }
+    }
+}
+
+impl Shape for Dim3 {
+    type Vec<T> = Vec3<T>;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> Vec3<E::Output> {
+        Vec3 {
+            x: collect_axis(expr, 0),
+            y: collect_axis(expr, 1),
+            z: collect_axis(expr, 2),
+        }
+    }
+}
+
+impl<const N: usize> Shape for DimN<N> {
+    type Vec<T> = VecN<T, N>;
+
+    fn eval<E: VExpr<Shape=Self>>(expr: &E) -> VecN<E::Output, N> {
+        VecN {
+            columns: std::array::from_fn(|axis| collect_axis(expr, axis)),
+        }
+    }
+}
+
+macro_rules! combine {
+    ($($lhs:ident + $rhs:ident = $out:ident),*) => {
+        $(
+            impl Combine<$rhs> for $lhs {
+                type Output = $out;
+            }
+        )*
+    };
+}
+
+combine!(
+    Dim0 + Dim0 = Dim0, Dim0 + Dim1 = Dim1, Dim0 + Dim2 = Dim2, Dim0 + Dim3 = Dim3,
+    Dim1 + Dim0 = Dim1, Dim1 + Dim1 = Dim1, Dim1 + Dim2 = Dim2, Dim1 + Dim3 = Dim3,
+    Dim2 + Dim0 = Dim2, Dim2 + Dim1 = Dim2, Dim2 + Dim2 = Dim2,
+    Dim3 + Dim0 = Dim3, Dim3 + Dim1 = Dim3, Dim3 + Dim3 = Dim3
+);
+
+impl<const N: usize> Combine<Dim0> for DimN<N> {
+    type Output = DimN<N>;
+}
+
+impl<const N: usize> Combine<Dim1> for DimN<N> {
+    type Output = DimN<N>;
+}
+
+impl<const N: usize> Combine<DimN<N>> for DimN<N> {
+    type Output = DimN<N>;
+}
+
+impl<const N: usize> Combine<DimN<N>> for Dim0 {
+    type Output = DimN<N>;
+}
+
+impl<const N: usize> Combine<DimN<N>> for Dim1 {
+    type Output = DimN<N>;
+}
+
+fn combine_size(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            assert_len!(a, b);
+            Some(a.min(b))
+        }
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+fn try_combine_size(a: Option<usize>, b: Option<usize>) -> Result<Option<usize>, SimdVecError> {
+    match (a, b) {
+        (Some(a), Some(b)) => check_len(a, b).map(|_| Some(a)),
+        (a, None) => Ok(a),
+        (None, b) => Ok(b),
+    }
 }
 
-impl<'a, A, B> VMul<'a, A, B> {
-    pub fn new(a: &'a A, b: &'a B) -> Self {
+impl<T: Copy> VExpr for &T {
+    type Output = T;
+    type Shape = Dim0;
+
+    fn size(&self) -> Option<usize> {
+        None
+    }
+
+    fn get(&self, _index: usize, _axis: usize) -> T {
+        **self
+    }
+
+    fn value(&self) -> Option<T> {
+        Some(**self)
+    }
+}
+
+impl<T: Copy> VExpr for &Vec1<T> {
+    type Output = T;
+    type Shape = Dim1;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+
+    fn get(&self, index: usize, _axis: usize) -> T {
+        self.values[index]
+    }
+
+    fn column(&self, _axis: usize) -> Option<&Vec1<T>> {
+        Some(self)
+    }
+}
+
+impl<T: Copy> VExpr for &Vec2<T> {
+    type Output = T;
+    type Shape = Dim2;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.x.len())
+    }
+
+    fn get(&self, index: usize, axis: usize) -> T {
+        match axis {
+            0 => self.x.values[index],
+            _ => self.y.values[index],
+        }
+    }
+
+    fn column(&self, axis: usize) -> Option<&Vec1<T>> {
+        match axis {
+            0 => Some(&self.x),
+            _ => Some(&self.y),
+        }
+    }
+}
+
+impl<T: Copy> VExpr for &Vec3<T> {
+    type Output = T;
+    type Shape = Dim3;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.x.len())
+    }
+
+    fn get(&self, index: usize, axis: usize) -> T {
+        match axis {
+            0 => self.x.values[index],
+            1 => self.y.values[index],
+            _ => self.z.values[index],
+        }
+    }
+
+    fn column(&self, axis: usize) -> Option<&Vec1<T>> {
+        match axis {
+            0 => Some(&self.x),
+            1 => Some(&self.y),
+            _ => Some(&self.z),
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> VExpr for &VecN<T, N> {
+    type Output = T;
+    type Shape = DimN<N>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn get(&self, index: usize, axis: usize) -> T {
+        self.columns[axis].values[index]
+    }
+
+    fn column(&self, axis: usize) -> Option<&Vec1<T>> {
+        Some(&self.columns[axis])
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct VMul<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> VMul<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct VDiv<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> VDiv<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct VAdd<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> VAdd<A, B> {
+    pub fn new(a: A, b: B) -> Self {
         Self { a, b }
     }
 }
 
 #[derive(Debug, Copy, Clone)]
-pub struct VDiv<'a, A, B> {
-    pub a: &'a A,
-    pub b: &'a B,
+pub struct VSub<A, B> {
+    pub a: A,
+    pub b: B,
 }
 
-impl<'a, A, B> VDiv<'a, A, B> {
-    pub fn new(a: &'a A, b: &'a B) -> Self {
+impl<A, B> VSub<A, B> {
+    pub fn new(a: A, b: B) -> Self {
         Self { a, b }
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+pub struct VNeg<A> {
+    pub a: A,
+}
+
+impl<A> VNeg<A> {
+    pub fn new(a: A) -> Self {
+        Self { a }
+    }
+}
+
+macro_rules! binary_expr {
+    ($node:ident, $trait:ident, $op:ident) => {
+        impl<A: VExpr, B: VExpr> VExpr for $node<A, B>
+            where
+                A::Output: $trait<B::Output>,
+                <A::Output as $trait<B::Output>>::Output: Copy,
+                A::Shape: Combine<B::Shape>,
+        {
+            type Output = <A::Output as $trait<B::Output>>::Output;
+            type Shape = <A::Shape as Combine<B::Shape>>::Output;
+
+            fn size(&self) -> Option<usize> {
+                combine_size(self.a.size(), self.b.size())
+            }
+
+            fn try_size(&self) -> Result<Option<usize>, SimdVecError> {
+                try_combine_size(self.a.try_size()?, self.b.try_size()?)
+            }
+
+            fn get(&self, index: usize, axis: usize) -> Self::Output {
+                self.a.get(index, axis).$op(self.b.get(index, axis))
+            }
+        }
+    };
+}
+
+binary_expr!(VMul, Mul, mul);
+binary_expr!(VDiv, Div, div);
+binary_expr!(VAdd, Add, add);
+binary_expr!(VSub, Sub, sub);
+
+impl<A: VExpr> VExpr for VNeg<A>
+    where
+        A::Output: Neg,
+        <A::Output as Neg>::Output: Copy,
+{
+    type Output = <A::Output as Neg>::Output;
+    type Shape = A::Shape;
+
+    fn size(&self) -> Option<usize> {
+        self.a.size()
+    }
+
+    fn try_size(&self) -> Result<Option<usize>, SimdVecError> {
+        self.a.try_size()
+    }
+
+    fn get(&self, index: usize, axis: usize) -> Self::Output {
+        self.a.get(index, axis).neg()
+    }
+}
+
+macro_rules! expr_ops {
+    ([$($gen:tt)*] $t:ty) => {
+        impl<$($gen)*, R: VExpr> Add<R> for $t {
+            type Output = VAdd<Self, R>;
+
+            fn add(self, rhs: R) -> Self::Output {
+                VAdd::new(self, rhs)
+            }
+        }
+
+        impl<$($gen)*, R: VExpr> Sub<R> for $t {
+            type Output = VSub<Self, R>;
+
+            fn sub(self, rhs: R) -> Self::Output {
+                VSub::new(self, rhs)
+            }
+        }
+
+        impl<$($gen)*, R: VExpr> Mul<R> for $t {
+            type Output = VMul<Self, R>;
+
+            fn mul(self, rhs: R) -> Self::Output {
+                VMul::new(self, rhs)
+            }
+        }
+
+        impl<$($gen)*, R: VExpr> Div<R> for $t {
+            type Output = VDiv<Self, R>;
+
+            fn div(self, rhs: R) -> Self::Output {
+                VDiv::new(self, rhs)
+            }
+        }
+
+        impl<$($gen)*> Neg for $t {
+            type Output = VNeg<Self>;
+
+            fn neg(self) -> Self::Output {
+                VNeg::new(self)
+            }
+        }
+    };
+}
+
+expr_ops!(['a, T] &'a Vec1<T>);
+expr_ops!(['a, T] &'a Vec2<T>);
+expr_ops!(['a, T] &'a Vec3<T>);
+expr_ops!(['a, T, const N: usize] &'a VecN<T, N>);
+expr_ops!([A, B] VMul<A, B>);
+expr_ops!([A, B] VDiv<A, B>);
+expr_ops!([A, B] VAdd<A, B>);
+expr_ops!([A, B] VSub<A, B>);
+expr_ops!([A] VNeg<A>);
+
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::vecs::*;
     use physics::*;
 
@@ -52,4 +508,324 @@ mod tests {
 
         assert_eq!(expected, position);
     }
+
+    #[test]
+    fn typed_add_assign_vec3() {
+        let mut position: Vec3<Length> = Vec3 {
+            x: vec![0.0.into(), 1.0.into()].into(),
+            y: vec![0.5.into(), 1.5.into()].into(),
+            z: vec![1.0.into(), 2.0.into()].into(),
+        };
+
+        let velocity: Vec3<Speed> = Vec3 {
+            x: vec![0.5.into(), 1.5.into()].into(),
+            y: vec![0.0.into(), 1.0.into()].into(),
+            z: vec![(-1.0).into(), 2.0.into()].into(),
+        };
+
+        let dt: Vec1<Time> = Vec1 {
+            values: vec![2.0.into(), 2.0.into()],
+        };
+
+        let expected = Vec3 {
+            x: vec![1.0.into(), 4.0.into()].into(),
+            y: vec![0.5.into(), 3.5.into()].into(),
+            z: vec![(-1.0).into(), 6.0.into()].into(),
+        };
+
+        position += &velocity * &dt;
+
+        assert_eq!(expected, position);
+    }
+
+    #[test]
+    fn typed_add_assign_expression() {
+        let mut position: Vec2<Length> = Vec2 {
+            x: vec![0.0.into(), 1.0.into()].into(),
+            y: vec![0.5.into(), 1.5.into()].into(),
+        };
+
+        let velocity: Vec2<Speed> = Vec2 {
+            x: vec![0.5.into(), 1.5.into()].into(),
+            y: vec![0.0.into(), 1.0.into()].into(),
+        };
+
+        let acceleration: Vec2<Acceleration> = Vec2 {
+            x: vec![1.0.into(), 0.0.into()].into(),
+            y: vec![0.0.into(), (-1.0).into()].into(),
+        };
+
+        let dt: Vec1<Time> = Vec1 {
+            values: vec![2.0.into(), 2.0.into()],
+        };
+
+        let expected = Vec2 {
+            x: vec![5.0.into(), 4.0.into()].into(),
+            y: vec![0.5.into(), (-0.5).into()].into(),
+        };
+
+        position += &velocity * &dt + &acceleration * &dt * &dt;
+
+        assert_eq!(expected, position);
+    }
+
+    #[test]
+    fn typed_add_assign_scalar_broadcast() {
+        let mut position: Vec2<Length> = Vec2 {
+            x: vec![0.0.into(), 1.0.into()].into(),
+            y: vec![0.5.into(), 1.5.into()].into(),
+        };
+
+        let velocity: Vec2<Speed> = Vec2 {
+            x: vec![0.5.into(), 1.5.into()].into(),
+            y: vec![0.0.into(), 1.0.into()].into(),
+        };
+
+        let dt = Time::in_seconds(2.0);
+
+        let expected = Vec2 {
+            x: vec![1.0.into(), 4.0.into()].into(),
+            y: vec![0.5.into(), 3.5.into()].into(),
+        };
+
+        position += &velocity * &dt;
+
+        assert_eq!(expected, position);
+    }
+
+    #[test]
+    fn sub_assign_nested_expression() {
+        let mut velocity = Vec2 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 4.0].into(),
+        };
+
+        let force = Vec2 {
+            x: vec![4.0, 6.0].into(),
+            y: vec![2.0, 0.0].into(),
+        };
+
+        let mass = Vec1::from(vec![2.0, 3.0]);
+        let dt = Vec1::from(vec![0.5, 1.0]);
+
+        let expected = Vec2 {
+            x: vec![0.0, 0.0].into(),
+            y: vec![2.5, 4.0].into(),
+        };
+
+        velocity -= (&force / &mass) * &dt;
+
+        assert_eq!(expected, velocity);
+    }
+
+    #[test]
+    fn add_assign_negation_and_difference() {
+        let mut v1 = Vec1::from(vec![1.0, 2.0, 3.0]);
+        let v2 = Vec1::from(vec![1.0, 1.0, 1.0]);
+        let v3 = Vec1::from(vec![3.0, 2.0, 1.0]);
+
+        v1 += -&v2;
+        assert_eq!(vec![0.0, 1.0, 2.0], v1.values);
+
+        v1 -= &v3 - &v2;
+        assert_eq!(vec![-2.0, 0.0, 2.0], v1.values);
+    }
+
+    #[test]
+    fn expression_matches_simd_path() {
+        let a = Vec1::from((0..19).map(|i| i as f32).collect::<Vec<_>>());
+        let b = Vec1::from((0..19).map(|i| (i * 2) as f32).collect::<Vec<_>>());
+
+        let mut fused = Vec1::from(vec![1.0f32; 19]);
+        let mut lazy = fused.clone();
+
+        fused += &a * &b;
+        lazy += &a * &b + &a * &0.0;
+
+        assert_eq!(fused, lazy);
+    }
+
+    #[test]
+    fn typed_eval() {
+        let speed: Vec1<Speed> = Vec1 {
+            values: vec![2.0.into(), 3.0.into()],
+        };
+
+        let time: Vec1<Time> = Vec1 {
+            values: vec![1.0.into(), 2.0.into()],
+        };
+
+        let expected: Vec1<Length> = Vec1 {
+            values: vec![2.0.into(), 6.0.into()],
+        };
+
+        assert_eq!(expected, (&speed * &time).eval());
+    }
+
+    #[test]
+    fn eval_vec2() {
+        let a = Vec2 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 4.0].into(),
+        };
+
+        let b = Vec2 {
+            x: vec![5.0, 6.0].into(),
+            y: vec![7.0, 8.0].into(),
+        };
+
+        let expected = Vec2 {
+            x: vec![-6.0, -8.0].into(),
+            y: vec![-10.0, -12.0].into(),
+        };
+
+        assert_eq!(expected, (-(&a + &b)).eval());
+        assert_eq!(a, (&a).eval());
+    }
+
+    #[test]
+    fn eval_scalar() {
+        assert_eq!(6.0, VMul::new(&2.0, &3.0).eval());
+    }
+
+    fn column(offset: f64) -> Vec1<f64> {
+        Vec1::from((0..19).map(|i| i as f64 + offset).collect::<Vec<_>>())
+    }
+
+    fn columns<L: VExpr<Output=f64>>(lhs: L, axes: usize) -> Vec<Vec<f64>> {
+        expected(lhs, &0.0, axes, |a, _| a)
+    }
+
+    fn expected<L: VExpr<Output=f64>, R: VExpr<Output=f64>>(lhs: L, rhs: R, axes: usize, f: fn(f64, f64) -> f64) -> Vec<Vec<f64>> {
+        let len = lhs.size().unwrap();
+
+        (0..axes)
+            .map(|axis| (0..len).map(|i| f(lhs.get(i, axis), rhs.get(i, axis))).collect())
+            .collect()
+    }
+
+    /// Checks every assignment op of `$lhs` with each rhs against the same op applied element by element.
+    macro_rules! assign_matrix {
+        ($lhs:ident, $axes:expr, [$($rhs:expr),+], [$($scalar:expr),+]) => {
+            $(
+                assign_matrix!(@check $lhs, $axes, $rhs, $rhs, +=, |a, b| a + b);
+                assign_matrix!(@check $lhs, $axes, $rhs, $rhs, -=, |a, b| a - b);
+                assign_matrix!(@check $lhs, $axes, $rhs, $rhs, *=, |a, b| a * b);
+                assign_matrix!(@check $lhs, $axes, $rhs, $rhs, /=, |a, b| a / b);
+            )+
+            $(
+                assign_matrix!(@check $lhs, $axes, $scalar, &$scalar, +=, |a, b| a + b);
+                assign_matrix!(@check $lhs, $axes, $scalar, &$scalar, -=, |a, b| a - b);
+                assign_matrix!(@check $lhs, $axes, $scalar, &$scalar, *=, |a, b| a * b);
+                assign_matrix!(@check $lhs, $axes, $scalar, &$scalar, /=, |a, b| a / b);
+            )+
+        };
+        (@check $lhs:ident, $axes:expr, $rhs:expr, $expr:expr, $op:tt, $f:expr) => {{
+            let mut lhs = $lhs.clone();
+            let expected = expected(&lhs, $expr, $axes, $f);
+
+            lhs $op $rhs;
+
+            assert_eq!(expected, columns(&lhs, $axes), "{} {} {}", stringify!($lhs), stringify!($op), stringify!($rhs));
+        }};
+    }
+
+    #[test]
+    fn assignment_matrix() {
+        let a = column(1.0);
+        let b = column(3.0);
+        let v2 = Vec2 { x: column(2.0), y: column(5.0) };
+        let v3 = Vec3 { x: column(2.0), y: column(5.0), z: column(7.0) };
+        let v4 = VecN { columns: [column(2.0), column(4.0), column(6.0), column(8.0)] };
+
+        assign_matrix!(a, 1, [&b, &a * &b, &a / &b, &b + &a, &b - &a, -&b, &b * &2.0, &b / &2.0], [2.0, 0.5]);
+        assign_matrix!(v2, 2, [&v2, &b, &v2 * &b, &v2 / &b, &v2 * &2.0, &v2 / &2.0, &a * &b, &v2 + &a, &b - &v2, -&v2], [2.0]);
+        assign_matrix!(v3, 3, [&v3, &b, &v3 * &a, &v3 / &b, &v3 / &2.0, &a / &b, &b - &v3, -&v3], [0.5]);
+        assign_matrix!(v4, 4, [&v4, &a, &v4 * &a, &v4 / &4.0, &a * &b, &a + &v4, -&v4], [4.0]);
+    }
+
+    #[test]
+    fn unit_changing_assignments() {
+        let length = |values: Vec<f64>| Vec1::from(values.into_iter().map(Length::from).collect::<Vec<_>>());
+
+        let mut position = Vec2 {
+            x: length(vec![2.0, 4.0]),
+            y: length(vec![6.0, 8.0]),
+        };
+        let mut velocity: Vec2<Speed> = Vec2 {
+            x: vec![1.0.into(), 2.0.into()].into(),
+            y: vec![3.0.into(), 4.0.into()].into(),
+        };
+        let dt = Vec1::from(vec![Time::from(1.0), Time::from(0.5)]);
+        let mut scale = Vec1::from(vec![1.0, 2.0]);
+
+        position *= 2.0;
+        assert_eq!(Vec2 { x: length(vec![4.0, 8.0]), y: length(vec![12.0, 16.0]) }, position);
+
+        position /= &scale;
+        assert_eq!(Vec2 { x: length(vec![4.0, 4.0]), y: length(vec![12.0, 8.0]) }, position);
+
+        position -= &velocity * &dt;
+        assert_eq!(Vec2 { x: length(vec![3.0, 3.0]), y: length(vec![9.0, 6.0]) }, position);
+
+        velocity += &position / &Time::from(3.0);
+        assert_eq!(Vec2 { x: vec![2.0.into(), 3.0.into()].into(), y: vec![6.0.into(), 6.0.into()].into() }, velocity);
+
+        velocity /= 2.0;
+        velocity -= &position.x / &dt;
+        assert_eq!(Vec2 { x: vec![(-2.0).into(), (-4.5).into()].into(), y: vec![0.0.into(), (-3.0).into()].into() }, velocity);
+
+        scale *= &position.y / &position.x;
+        assert_eq!(vec![3.0, 4.0], scale.values);
+
+        let mut x = position.x.clone();
+        x /= 3.0;
+        x += &velocity.y * &dt;
+        assert_eq!(length(vec![1.0, -0.5]), x);
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Ratio(f64);
+
+    impl Scalar for Ratio {}
+
+    impl MulAssign<Ratio> for Length {
+        fn mul_assign(&mut self, rhs: Ratio) {
+            *self *= rhs.0;
+        }
+    }
+
+    impl DivAssign<Ratio> for Length {
+        fn div_assign(&mut self, rhs: Ratio) {
+            *self /= rhs.0;
+        }
+    }
+
+    #[test]
+    fn unit_typed_scalar_assignments() {
+        let length = |values: Vec<f64>| Vec1::from(values.into_iter().map(Length::from).collect::<Vec<_>>());
+
+        let mut x = length(vec![1.0, 2.0]);
+        x += Length::from(3.0);
+        assert_eq!(length(vec![4.0, 5.0]), x);
+
+        x /= Ratio(4.0);
+        assert_eq!(length(vec![1.0, 1.25]), x);
+
+        let mut position = Vec2 {
+            x: length(vec![2.0, 4.0]),
+            y: length(vec![6.0, 8.0]),
+        };
+
+        position -= Length::from(1.0);
+        assert_eq!(Vec2 { x: length(vec![1.0, 3.0]), y: length(vec![5.0, 7.0]) }, position);
+
+        position *= Ratio(2.0);
+        assert_eq!(Vec2 { x: length(vec![2.0, 6.0]), y: length(vec![10.0, 14.0]) }, position);
+
+        let mut v = VecN { columns: [length(vec![1.0]), length(vec![2.0]), length(vec![3.0])] };
+        v += Length::from(1.0);
+        v /= Ratio(2.0);
+        assert_eq!(VecN { columns: [length(vec![1.0]), length(vec![1.5]), length(vec![2.0])] }, v);
+    }
 }
\ No newline at end of file
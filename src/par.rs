@@ -0,0 +1,103 @@
+//! Parallel execution with `rayon`.
+//!
+//! Slices longer than `threshold()` are split into chunks of at least that many elements
+//! and processed on the rayon thread pool. This covers the operators for every element type,
+//! using the SIMD kernels where they apply. Element-wise operations produce the same values
+//! however the work is split, the parallel sums follow the same summation order as their
+//! serial counterparts and `par_dot` sums fixed chunks of `threshold()` elements, so results
+//! do not depend on the number of threads.
+
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const DEFAULT_THRESHOLD: usize = 1 << 16;
+
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+/// The minimum number of elements that each parallel chunk processes.
+pub fn threshold() -> usize {
+    THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the minimum chunk length. Smaller values spread shorter columns across threads
+/// at the cost of more scheduling overhead.
+pub fn set_threshold(len: usize) {
+    THRESHOLD.store(len.max(1), Ordering::Relaxed);
+}
+
+pub(crate) fn chunks1<T: Send>(dst: &mut [T], f: impl Fn(&mut [T]) + Sync) {
+    let len = threshold();
+
+    if dst.len() <= len {
+        f(dst);
+    } else {
+        dst.par_chunks_mut(len).for_each(&f);
+    }
+}
+
+/// `chunks1`, also passing the index of each chunk's first element.
+pub(crate) fn chunks1_indexed<T: Send>(dst: &mut [T], f: impl Fn(usize, &mut [T]) + Sync) {
+    let len = threshold();
+
+    if dst.len() <= len {
+        f(0, dst);
+    } else {
+        dst.par_chunks_mut(len)
+            .enumerate()
+            .for_each(|(i, dst)| f(i * len, dst));
+    }
+}
+
+pub(crate) fn chunks2<T: Send, A: Sync>(dst: &mut [T], a: &[A], f: impl Fn(&mut [T], &[A]) + Sync) {
+    let len = threshold();
+
+    if dst.len() <= len {
+        f(dst, a);
+    } else {
+        dst.par_chunks_mut(len)
+            .zip(a.par_chunks(len))
+            .for_each(|(dst, a)| f(dst, a));
+    }
+}
+
+pub(crate) fn chunks3<T: Send, A: Sync, B: Sync>(dst: &mut [T], a: &[A], b: &[B], f: impl Fn(&mut [T], &[A], &[B]) + Sync) {
+    let len = threshold();
+
+    if dst.len() <= len {
+        f(dst, a, b);
+    } else {
+        dst.par_chunks_mut(len)
+            .zip(a.par_chunks(len))
+            .zip(b.par_chunks(len))
+            .for_each(|((dst, a), b)| f(dst, a, b));
+    }
+}
+
+/// Splits a pair of slices into tiles of `len` elements, passing each with the index of its first element.
+/// Unlike the chunks above this ignores `threshold()`, as it serves kernels whose work per element
+/// grows with the size of the whole input.
+pub(crate) fn tiles<T: Send>(a: &mut [T], b: &mut [T], len: usize, f: impl Fn(usize, &mut [T], &mut [T]) + Sync) {
+    a.par_chunks_mut(len)
+        .zip(b.par_chunks_mut(len))
+        .enumerate()
+        .for_each(|(i, (a, b))| f(i * len, a, b));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_cover_every_element() {
+        let len = 4 * threshold() + 17;
+        let mut dst = vec![1.0; len];
+        let a = (0..len).map(|i| i as f64).collect::<Vec<_>>();
+
+        chunks2(&mut dst, &a, |dst, a| {
+            assert!(dst.len() <= threshold());
+            dst.iter_mut().zip(a).for_each(|(d, a)| *d += a);
+        });
+
+        assert!(dst.iter().enumerate().all(|(i, d)| *d == i as f64 + 1.0));
+    }
+}
@@ -0,0 +1,620 @@
+use crate::vecs::{Vec1, Vec2};
+use std::any::Any;
+
+#[cfg(feature = "rayon")]
+pub(crate) use crate::par::{chunks1, chunks1_indexed, chunks2, chunks3};
+#[cfg(feature = "rayon")]
+pub(crate) use crate::par::tiles;
+
+mod scalar;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum UnaryOp {
+    Sqrt,
+    Abs,
+    Recip,
+    Sin,
+    Cos,
+    Tan,
+    Atan,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    /// `dst = atan2(dst, a)`
+    Atan2,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum ScalarOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// `dst ± a * b` and `dst ± a / b`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum FusedOp {
+    MulAdd,
+    MulSub,
+    DivAdd,
+    DivSub,
+}
+
+/// Element types with explicit SIMD kernels.
+///
+/// Kernels are selected at runtime from the best instruction set the CPU supports
+/// (AVX2 + FMA, AVX2, SSE2), falling back to a scalar loop on other targets.
+/// Note that the FMA kernels round `a * b` only once, so `MulAdd`/`MulSub` results
+/// can differ in the last bit from the non-FMA paths.
+pub(crate) trait SimdFloat: Copy + Send + Sync + 'static {
+    fn unary(op: UnaryOp, dst: &mut [Self]);
+
+    fn binary(op: BinaryOp, dst: &mut [Self], a: &[Self]);
+
+    fn scalar(op: ScalarOp, dst: &mut [Self], s: Self);
+
+    fn fused(op: FusedOp, dst: &mut [Self], a: &[Self], b: &[Self]);
+
+    fn fused_scalar(op: FusedOp, dst: &mut [Self], a: &[Self], s: Self);
+
+    /// Adds the pull of each source, with positions `sources[0..2]` and mass `sources[2]`, to the
+    /// acceleration of each target: `a += m d / (|d|^2 + softening2)^(3/2)` with `d` the offset to the source.
+    fn gravity(acceleration: [&mut [Self]; 2], position: [&[Self]; 2], sources: [&[Self]; 3], softening2: Self);
+}
+
+macro_rules! dispatch {
+    ($($module:ident)::+, $kernel:ident($($arg:expr),*), $fallback:expr) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return unsafe { x86::$($module)::+::avx2::$kernel($($arg),*) };
+            }
+            if is_x86_feature_detected!("sse2") {
+                return unsafe { x86::$($module)::+::sse2::$kernel($($arg),*) };
+            }
+        }
+        $fallback
+    }};
+}
+
+macro_rules! dispatch_fma {
+    ($t:ident, $kernel:ident($($arg:expr),*), $fallback:expr) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                return unsafe { x86::$t::fma::$kernel($($arg),*) };
+            }
+        }
+        dispatch!($t, $kernel($($arg),*), $fallback)
+    }};
+}
+
+macro_rules! simd_float {
+    ($t:ident) => {
+        impl SimdFloat for $t {
+            fn unary(op: UnaryOp, dst: &mut [$t]) {
+                match op {
+                    UnaryOp::Sqrt => dispatch!($t, sqrt(dst), scalar::unary(dst, $t::sqrt)),
+                    UnaryOp::Abs => dispatch!($t, abs(dst), scalar::unary(dst, $t::abs)),
+                    UnaryOp::Recip => dispatch!($t, recip(dst), scalar::unary(dst, $t::recip)),
+                    UnaryOp::Sin => dispatch!(trig::$t, sin(dst), scalar::unary(dst, $t::sin)),
+                    UnaryOp::Cos => dispatch!(trig::$t, cos(dst), scalar::unary(dst, $t::cos)),
+                    UnaryOp::Tan => dispatch!(trig::$t, tan(dst), scalar::unary(dst, $t::tan)),
+                    UnaryOp::Atan => dispatch!(trig::$t, atan(dst), scalar::unary(dst, $t::atan)),
+                }
+            }
+
+            fn binary(op: BinaryOp, dst: &mut [$t], a: &[$t]) {
+                match op {
+                    BinaryOp::Add => dispatch!($t, add(dst, a), scalar::binary(dst, a, |d, a| d + a)),
+                    BinaryOp::Sub => dispatch!($t, sub(dst, a), scalar::binary(dst, a, |d, a| d - a)),
+                    BinaryOp::Mul => dispatch!($t, mul(dst, a), scalar::binary(dst, a, |d, a| d * a)),
+                    BinaryOp::Div => dispatch!($t, div(dst, a), scalar::binary(dst, a, |d, a| d / a)),
+                    BinaryOp::Atan2 => dispatch!(trig::$t, atan2(dst, a), scalar::binary(dst, a, $t::atan2)),
+                }
+            }
+
+            fn scalar(op: ScalarOp, dst: &mut [$t], s: $t) {
+                match op {
+                    ScalarOp::Add => dispatch!($t, add_scalar(dst, s), scalar::scalar(dst, s, |d, s| d + s)),
+                    ScalarOp::Sub => dispatch!($t, sub_scalar(dst, s), scalar::scalar(dst, s, |d, s| d - s)),
+                    ScalarOp::Mul => dispatch!($t, mul_scalar(dst, s), scalar::scalar(dst, s, |d, s| d * s)),
+                    ScalarOp::Div => dispatch!($t, div_scalar(dst, s), scalar::scalar(dst, s, |d, s| d / s)),
+                }
+            }
+
+            fn fused(op: FusedOp, dst: &mut [$t], a: &[$t], b: &[$t]) {
+                match op {
+                    FusedOp::MulAdd => dispatch_fma!($t, mul_add(dst, a, b), scalar::fused(dst, a, b, |d, a, b| d + a * b)),
+                    FusedOp::MulSub => dispatch_fma!($t, mul_sub(dst, a, b), scalar::fused(dst, a, b, |d, a, b| d - a * b)),
+                    FusedOp::DivAdd => dispatch!($t, div_add(dst, a, b), scalar::fused(dst, a, b, |d, a, b| d + a / b)),
+                    FusedOp::DivSub => dispatch!($t, div_sub(dst, a, b), scalar::fused(dst, a, b, |d, a, b| d - a / b)),
+                }
+            }
+
+            fn fused_scalar(op: FusedOp, dst: &mut [$t], a: &[$t], s: $t) {
+                match op {
+                    FusedOp::MulAdd => dispatch_fma!($t, mul_add_scalar(dst, a, s), scalar::fused_scalar(dst, a, s, |d, a, s| d + a * s)),
+                    FusedOp::MulSub => dispatch_fma!($t, mul_sub_scalar(dst, a, s), scalar::fused_scalar(dst, a, s, |d, a, s| d - a * s)),
+                    FusedOp::DivAdd => dispatch!($t, div_add_scalar(dst, a, s), scalar::fused_scalar(dst, a, s, |d, a, s| d + a / s)),
+                    FusedOp::DivSub => dispatch!($t, div_sub_scalar(dst, a, s), scalar::fused_scalar(dst, a, s, |d, a, s| d - a / s)),
+                }
+            }
+
+            fn gravity(acceleration: [&mut [$t]; 2], position: [&[$t]; 2], sources: [&[$t]; 3], softening2: $t) {
+                dispatch!($t, gravity(acceleration, position, sources, softening2), scalar::gravity(acceleration, position, sources, softening2))
+            }
+        }
+    };
+}
+
+simd_float!(f32);
+simd_float!(f64);
+
+// Without rayon every operation runs over the whole slice at once.
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn chunks1<T>(dst: &mut [T], f: impl Fn(&mut [T])) {
+    f(dst)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn chunks1_indexed<T>(dst: &mut [T], f: impl Fn(usize, &mut [T])) {
+    f(0, dst)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn chunks2<T, A>(dst: &mut [T], a: &[A], f: impl Fn(&mut [T], &[A])) {
+    f(dst, a)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn chunks3<T, A, B>(dst: &mut [T], a: &[A], b: &[B], f: impl Fn(&mut [T], &[A], &[B])) {
+    f(dst, a, b)
+}
+
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn tiles<T>(a: &mut [T], b: &mut [T], len: usize, f: impl Fn(usize, &mut [T], &mut [T])) {
+    a.chunks_mut(len)
+        .zip(b.chunks_mut(len))
+        .enumerate()
+        .for_each(|(i, (a, b))| f(i * len, a, b));
+}
+
+// The entry points below return false when the element types are not all the same
+// SIMD float, in which case the caller falls back to its generic implementation.
+
+pub(crate) fn unary<T: 'static>(op: UnaryOp, lhs: &mut Vec1<T>) -> bool {
+    unary_as::<f32, _>(op, lhs) || unary_as::<f64, _>(op, lhs)
+}
+
+fn unary_as<F: SimdFloat, T: 'static>(op: UnaryOp, lhs: &mut Vec1<T>) -> bool {
+    match (lhs as &mut dyn Any).downcast_mut::<Vec1<F>>() {
+        Some(lhs) => {
+            chunks1(lhs.as_mut_slice(), |dst| F::unary(op, dst));
+            true
+        }
+        None => false,
+    }
+}
+
+pub(crate) fn binary<T1: 'static, T2: 'static>(op: BinaryOp, lhs: &mut Vec1<T1>, rhs: &Vec1<T2>) -> bool {
+    assert_len!(lhs.len(), rhs.len());
+
+    binary_as::<f32, _, _>(op, lhs, rhs) || binary_as::<f64, _, _>(op, lhs, rhs)
+}
+
+fn binary_as<F: SimdFloat, T1: 'static, T2: 'static>(op: BinaryOp, lhs: &mut Vec1<T1>, rhs: &Vec1<T2>) -> bool {
+    let lhs = (lhs as &mut dyn Any).downcast_mut::<Vec1<F>>();
+    let rhs = (rhs as &dyn Any).downcast_ref::<Vec1<F>>();
+
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => {
+            chunks2(lhs.as_mut_slice(), rhs.as_slice(), |dst, a| F::binary(op, dst, a));
+            true
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn scalar<T1: 'static, T2: 'static>(op: ScalarOp, lhs: &mut Vec1<T1>, rhs: &T2) -> bool {
+    scalar_as::<f32, _, _>(op, lhs, rhs) || scalar_as::<f64, _, _>(op, lhs, rhs)
+}
+
+fn scalar_as<F: SimdFloat, T1: 'static, T2: 'static>(op: ScalarOp, lhs: &mut Vec1<T1>, rhs: &T2) -> bool {
+    let lhs = (lhs as &mut dyn Any).downcast_mut::<Vec1<F>>();
+    let rhs = (rhs as &dyn Any).downcast_ref::<F>();
+
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => {
+            let rhs = *rhs;
+            chunks1(lhs.as_mut_slice(), |dst| F::scalar(op, dst, rhs));
+            true
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn fused<T1: 'static, T2: 'static, T3: 'static>(op: FusedOp, lhs: &mut Vec1<T1>, a: &Vec1<T2>, b: &Vec1<T3>) -> bool {
+    assert_len!(lhs.len(), a.len());
+    assert_len!(lhs.len(), b.len());
+
+    fused_as::<f32, _, _, _>(op, lhs, a, b) || fused_as::<f64, _, _, _>(op, lhs, a, b)
+}
+
+fn fused_as<F: SimdFloat, T1: 'static, T2: 'static, T3: 'static>(op: FusedOp, lhs: &mut Vec1<T1>, a: &Vec1<T2>, b: &Vec1<T3>) -> bool {
+    let lhs = (lhs as &mut dyn Any).downcast_mut::<Vec1<F>>();
+    let a = (a as &dyn Any).downcast_ref::<Vec1<F>>();
+    let b = (b as &dyn Any).downcast_ref::<Vec1<F>>();
+
+    match (lhs, a, b) {
+        (Some(lhs), Some(a), Some(b)) => {
+            chunks3(lhs.as_mut_slice(), a.as_slice(), b.as_slice(), |dst, a, b| F::fused(op, dst, a, b));
+            true
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn fused_scalar<T1: 'static, T2: 'static, T3: 'static>(op: FusedOp, lhs: &mut Vec1<T1>, a: &Vec1<T2>, s: &T3) -> bool {
+    assert_len!(lhs.len(), a.len());
+
+    fused_scalar_as::<f32, _, _, _>(op, lhs, a, s) || fused_scalar_as::<f64, _, _, _>(op, lhs, a, s)
+}
+
+fn fused_scalar_as<F: SimdFloat, T1: 'static, T2: 'static, T3: 'static>(op: FusedOp, lhs: &mut Vec1<T1>, a: &Vec1<T2>, s: &T3) -> bool {
+    let lhs = (lhs as &mut dyn Any).downcast_mut::<Vec1<F>>();
+    let a = (a as &dyn Any).downcast_ref::<Vec1<F>>();
+    let s = (s as &dyn Any).downcast_ref::<F>();
+
+    match (lhs, a, s) {
+        (Some(lhs), Some(a), Some(s)) => {
+            let s = *s;
+            chunks2(lhs.as_mut_slice(), a.as_slice(), |dst, a| F::fused_scalar(op, dst, a, s));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Targets are processed in tiles, each passing over the sources one cache-sized tile at a time.
+const TARGET_TILE: usize = 256;
+const SOURCE_TILE: usize = 512;
+
+pub(crate) fn gravity<T: 'static>(acceleration: &mut Vec2<T>, position: &Vec2<T>, mass: &Vec1<T>, softening2: &T) -> bool {
+    gravity_as::<f32, _>(acceleration, position, mass, softening2) || gravity_as::<f64, _>(acceleration, position, mass, softening2)
+}
+
+fn gravity_as<F: SimdFloat, T: 'static>(acceleration: &mut Vec2<T>, position: &Vec2<T>, mass: &Vec1<T>, softening2: &T) -> bool {
+    let acceleration = (acceleration as &mut dyn Any).downcast_mut::<Vec2<F>>();
+    let position = (position as &dyn Any).downcast_ref::<Vec2<F>>();
+    let mass = (mass as &dyn Any).downcast_ref::<Vec1<F>>();
+    let softening2 = (softening2 as &dyn Any).downcast_ref::<F>();
+
+    match (acceleration, position, mass, softening2) {
+        (Some(acceleration), Some(position), Some(mass), Some(softening2)) => {
+            let softening2 = *softening2;
+            let (x, y, m) = (position.x.as_slice(), position.y.as_slice(), mass.as_slice());
+
+            tiles(acceleration.x.as_mut_slice(), acceleration.y.as_mut_slice(), TARGET_TILE, |start, ax, ay| {
+                let end = start + ax.len();
+                let sources = x.chunks(SOURCE_TILE).zip(y.chunks(SOURCE_TILE)).zip(m.chunks(SOURCE_TILE));
+
+                for ((sx, sy), sm) in sources {
+                    F::gravity([&mut *ax, &mut *ay], [&x[start..end], &y[start..end]], [sx, sy, sm], softening2);
+                }
+            });
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::VMul;
+
+    // integer-valued inputs keep products exact, so FMA and non-FMA paths agree bit for bit
+    fn values(len: usize, offset: f64) -> Vec<f64> {
+        (0..len).map(|i| (i as f64) + offset).collect()
+    }
+
+    #[test]
+    fn unary_matches_scalar_for_all_lengths() {
+        for len in 0..20 {
+            for &op in &[UnaryOp::Sqrt, UnaryOp::Abs, UnaryOp::Recip] {
+                let mut simd: Vec<f64> = values(len, -8.0).into_iter().map(|v| v * v * v.signum()).collect();
+                if op == UnaryOp::Sqrt {
+                    simd.iter_mut().for_each(|v| *v = v.abs());
+                }
+                let mut expected = simd.clone();
+
+                f64::unary(op, &mut simd);
+                scalar::unary(&mut expected, |d| match op {
+                    UnaryOp::Sqrt => d.sqrt(),
+                    UnaryOp::Abs => d.abs(),
+                    UnaryOp::Recip => 1.0 / d,
+                    _ => unreachable!(),
+                });
+
+                assert_eq!(expected, simd);
+            }
+        }
+    }
+
+    fn ulps<F: Into<f64> + Copy>(exact: F, fast: F, bits: fn(F) -> i64) -> i64 {
+        let (e, f) = (exact.into(), fast.into());
+        if e == f || (e.is_nan() && f.is_nan()) {
+            0
+        } else if e.is_sign_negative() != f.is_sign_negative() {
+            i64::MAX
+        } else {
+            (bits(exact) - bits(fast)).abs()
+        }
+    }
+
+    fn max_ulps_f64(input: &[f64], fast: &[f64], exact: fn(f64) -> f64) -> i64 {
+        input.iter().zip(fast.iter()).map(|(x, f)| ulps(exact(*x), *f, |v| v.to_bits() as i64)).max().unwrap_or(0)
+    }
+
+    fn max_ulps_f32(input: &[f32], fast: &[f32], exact: fn(f32) -> f32) -> i64 {
+        input.iter().zip(fast.iter()).map(|(x, f)| ulps(exact(*x), *f, |v| v.to_bits() as i64)).max().unwrap_or(0)
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn trig_error_bounds() {
+        // a dense grid, a sparse grid out to the fallback limit and the points nearest k * pi / 2
+        let mut f64s: Vec<f64> = (-100_000..100_000).map(|i| i as f64 * 1.37e-3).collect();
+        f64s.extend((-100_000..100_000).map(|i| i as f64 * 9.999_7));
+        f64s.extend((-1000..1000).map(|k| k as f64 * std::f64::consts::FRAC_PI_2));
+        let f32s: Vec<f32> = f64s.iter().map(|v| *v as f32).filter(|v| v.abs() <= 8192.0).collect();
+
+        macro_rules! check {
+            ($module:ident) => {{
+                let mut out = [f64s.clone(), f64s.clone(), f64s.clone(), f64s.clone()];
+                unsafe {
+                    x86::trig::f64::$module::sin(&mut out[0]);
+                    x86::trig::f64::$module::cos(&mut out[1]);
+                    x86::trig::f64::$module::tan(&mut out[2]);
+                    x86::trig::f64::$module::atan(&mut out[3]);
+                }
+                assert!(max_ulps_f64(&f64s, &out[0], f64::sin) <= 2);
+                assert!(max_ulps_f64(&f64s, &out[1], f64::cos) <= 2);
+                assert!(max_ulps_f64(&f64s, &out[2], f64::tan) <= 4);
+                assert!(max_ulps_f64(&f64s, &out[3], f64::atan) <= 2);
+
+                let mut out = [f32s.clone(), f32s.clone(), f32s.clone(), f32s.clone()];
+                unsafe {
+                    x86::trig::f32::$module::sin(&mut out[0]);
+                    x86::trig::f32::$module::cos(&mut out[1]);
+                    x86::trig::f32::$module::tan(&mut out[2]);
+                    x86::trig::f32::$module::atan(&mut out[3]);
+                }
+                assert!(max_ulps_f32(&f32s, &out[0], f32::sin) <= 2);
+                assert!(max_ulps_f32(&f32s, &out[1], f32::cos) <= 2);
+                assert!(max_ulps_f32(&f32s, &out[2], f32::tan) <= 4);
+                assert!(max_ulps_f32(&f32s, &out[3], f32::atan) <= 2);
+
+                let x: Vec<f64> = f64s.iter().rev().map(|v| v * 0.7).collect();
+                let mut y = f64s.clone();
+                unsafe { x86::trig::f64::$module::atan2(&mut y, &x) };
+                for ((y_in, x), fast) in f64s.iter().zip(x.iter()).zip(y.iter()) {
+                    assert!(ulps(y_in.atan2(*x), *fast, |v| v.to_bits() as i64) <= 2);
+                }
+            }};
+        }
+
+        if is_x86_feature_detected!("sse2") {
+            check!(sse2);
+        }
+        if is_x86_feature_detected!("avx2") {
+            check!(avx2);
+        }
+    }
+
+    #[test]
+    fn trig_special_values_match_std() {
+        let input = vec![0.0, -0.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN, 1e300, -1e7];
+
+        for &(op, exact) in &[(UnaryOp::Sin, f64::sin as fn(f64) -> f64), (UnaryOp::Cos, f64::cos), (UnaryOp::Tan, f64::tan), (UnaryOp::Atan, f64::atan)] {
+            let mut fast = input.clone();
+            f64::unary(op, &mut fast);
+
+            for (x, fast) in input.iter().zip(fast.iter()) {
+                let exact = exact(*x);
+                assert!(exact.to_bits() == fast.to_bits() || (exact.is_nan() && fast.is_nan()), "{:?}({}) = {}", op, x, fast);
+            }
+        }
+
+        let mut y = vec![0.0, -0.0, 0.0, -0.0, 1.0, -1.0, f64::INFINITY, 1.0];
+        let x = vec![0.0, 0.0, -0.0, -1.0, 0.0, f64::NEG_INFINITY, f64::INFINITY, f64::NAN];
+        let expected: Vec<f64> = y.iter().zip(x.iter()).map(|(y, x)| y.atan2(*x)).collect();
+
+        f64::binary(BinaryOp::Atan2, &mut y, &x);
+
+        for (expected, fast) in expected.iter().zip(y.iter()) {
+            assert!(expected.to_bits() == fast.to_bits() || (expected.is_nan() && fast.is_nan()));
+        }
+    }
+
+    #[test]
+    fn binary_matches_scalar_for_all_lengths() {
+        for len in 0..20 {
+            for &op in &[BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul, BinaryOp::Div] {
+                let a = values(len, 3.0);
+                let mut simd = values(len, 1.0);
+                let mut expected = simd.clone();
+
+                f64::binary(op, &mut simd, &a);
+                scalar::binary(&mut expected, &a, |d, a| match op {
+                    BinaryOp::Add => d + a,
+                    BinaryOp::Sub => d - a,
+                    BinaryOp::Mul => d * a,
+                    BinaryOp::Div => d / a,
+                    BinaryOp::Atan2 => unreachable!(),
+                });
+
+                assert_eq!(expected, simd);
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_ops_match_scalar_for_all_lengths() {
+        for len in 0..20 {
+            for &op in &[ScalarOp::Add, ScalarOp::Sub, ScalarOp::Mul, ScalarOp::Div] {
+                let mut simd = values(len, 1.0);
+                let mut expected = simd.clone();
+
+                f64::scalar(op, &mut simd, 3.0);
+                scalar::scalar(&mut expected, 3.0, |d, s| match op {
+                    ScalarOp::Add => d + s,
+                    ScalarOp::Sub => d - s,
+                    ScalarOp::Mul => d * s,
+                    ScalarOp::Div => d / s,
+                });
+
+                assert_eq!(expected, simd);
+            }
+        }
+    }
+
+    #[test]
+    fn fused_matches_scalar_for_all_lengths() {
+        for len in 0..20 {
+            for &op in &[FusedOp::MulAdd, FusedOp::MulSub, FusedOp::DivAdd, FusedOp::DivSub] {
+                let a: Vec<f32> = values(len, 2.0).into_iter().map(|v| v as f32).collect();
+                let b: Vec<f32> = values(len, 5.0).into_iter().map(|v| v as f32).collect();
+                let mut simd: Vec<f32> = values(len, 1.0).into_iter().map(|v| v as f32).collect();
+                let mut simd_scalar = simd.clone();
+                let mut expected = simd.clone();
+                let mut expected_scalar = simd.clone();
+
+                f32::fused(op, &mut simd, &a, &b);
+                f32::fused_scalar(op, &mut simd_scalar, &a, 4.0);
+
+                let f = |d: f32, a: f32, b: f32| match op {
+                    FusedOp::MulAdd => d + a * b,
+                    FusedOp::MulSub => d - a * b,
+                    FusedOp::DivAdd => d + a / b,
+                    FusedOp::DivSub => d - a / b,
+                };
+                scalar::fused(&mut expected, &a, &b, f);
+                scalar::fused_scalar(&mut expected_scalar, &a, 4.0, f);
+
+                assert_eq!(expected, simd);
+                assert_eq!(expected_scalar, simd_scalar);
+            }
+        }
+    }
+
+    // non-integer products are rounded, so FMA CPUs can differ from the scalar reference, by at most
+    // an ulp while the sum and difference do not cancel
+    #[test]
+    fn fused_mul_is_within_an_ulp_of_scalar() {
+        let len = 67;
+        let d = (0..len).map(|i| 64.0 + i as f64 * 0.37).collect::<Vec<_>>();
+        let a = (0..len).map(|i| 0.5 + i as f64 * 0.0731).collect::<Vec<_>>();
+        let b = (0..len).map(|i| 1.1 + i as f64 * 0.0173).collect::<Vec<_>>();
+
+        let ulps = |x: f64, y: f64| (x.to_bits() as i64 - y.to_bits() as i64).abs();
+        let ulps32 = |x: f32, y: f32| (x.to_bits() as i32 - y.to_bits() as i32).abs();
+
+        let (d1, a1, b1) = (Vec1::from(d.clone()), Vec1::from(a.clone()), Vec1::from(b.clone()));
+        let mut sum = d1.clone();
+        let mut difference = d1.clone();
+        let mut scaled = d1;
+        sum += VMul::new(&a1, &b1);
+        difference -= VMul::new(&a1, &b1);
+        scaled += VMul::new(&a1, &0.37);
+
+        let f32s = |v: &[f64]| Vec1::from(v.iter().map(|v| *v as f32).collect::<Vec<_>>());
+        let (d2, a2, b2) = (f32s(&d), f32s(&a), f32s(&b));
+        let mut sum32 = d2.clone();
+        sum32 += VMul::new(&a2, &b2);
+
+        for i in 0..len {
+            assert!(ulps(d[i] + a[i] * b[i], sum.values[i]) <= 1);
+            assert!(ulps(d[i] - a[i] * b[i], difference.values[i]) <= 1);
+            assert!(ulps(d[i] + a[i] * 0.37, scaled.values[i]) <= 1);
+            assert!(ulps32(d2.values[i] + a2.values[i] * b2.values[i], sum32.values[i]) <= 1);
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn each_instruction_set_matches_scalar() {
+        let a = values(19, 3.0);
+        let mut expected = values(19, 1.0);
+        scalar::scalar(&mut expected, 0.5, |d, s| d / s);
+
+        if is_x86_feature_detected!("sse2") {
+            let mut actual = values(19, 1.0);
+            unsafe { x86::f64::sse2::div_scalar(&mut actual, 0.5) };
+            assert_eq!(expected, actual);
+        }
+
+        if is_x86_feature_detected!("avx2") {
+            let mut actual = values(19, 1.0);
+            unsafe { x86::f64::avx2::div_scalar(&mut actual, 0.5) };
+            assert_eq!(expected, actual);
+        }
+
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            let mut actual = values(19, 1.0);
+            let mut expected = actual.clone();
+            scalar::fused_scalar(&mut expected, &a, 2.0, |d, a, s| d - a * s);
+
+            unsafe { x86::f64::fma::mul_sub_scalar(&mut actual, &a, 2.0) };
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn gravity_matches_scalar_for_all_lengths() {
+        for len in 0..20 {
+            let x = values(len, -9.5);
+            let y: Vec<f64> = x.iter().map(|x| 0.25 * x * x).collect();
+            let m = values(len, 1.0);
+
+            for &softening2 in &[0.0, 0.5] {
+                let (mut ax, mut ay) = (values(len, 0.0), values(len, 1.0));
+                let (mut expected_x, mut expected_y) = (ax.clone(), ay.clone());
+
+                f64::gravity([&mut ax, &mut ay], [&x, &y], [&x, &y, &m], softening2);
+                scalar::gravity([&mut expected_x, &mut expected_y], [&x, &y], [&x, &y, &m], softening2);
+
+                assert_eq!(expected_x, ax);
+                assert_eq!(expected_y, ay);
+                assert!(ax.iter().chain(&ay).all(|a| a.is_finite()));
+            }
+        }
+    }
+
+    #[test]
+    fn non_float_types_are_not_handled() {
+        let mut lhs = Vec1::from(vec![1, 2, 3]);
+        let rhs = Vec1::from(vec![1, 2, 3]);
+
+        assert!(!binary(BinaryOp::Add, &mut lhs, &rhs));
+        assert_eq!(vec![1, 2, 3], lhs.values);
+    }
+
+    #[test]
+    fn mixed_float_types_are_not_handled() {
+        let mut lhs = Vec1::from(vec![1.0f32, 2.0]);
+        let rhs = Vec1::from(vec![1.0f64, 2.0]);
+
+        assert!(!binary(BinaryOp::Add, &mut lhs, &rhs));
+    }
+}
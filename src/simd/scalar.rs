@@ -0,0 +1,44 @@
+use num_traits::Float;
+
+pub fn unary<T: Copy, F: Fn(T) -> T>(dst: &mut [T], f: F) {
+    dst.iter_mut()
+        .for_each(|d| *d = f(*d));
+}
+
+pub fn binary<T: Copy, F: Fn(T, T) -> T>(dst: &mut [T], a: &[T], f: F) {
+    dst.iter_mut()
+        .zip(a.iter())
+        .for_each(|(d, a)| *d = f(*d, *a));
+}
+
+pub fn scalar<T: Copy, F: Fn(T, T) -> T>(dst: &mut [T], s: T, f: F) {
+    dst.iter_mut()
+        .for_each(|d| *d = f(*d, s));
+}
+
+pub fn fused<T: Copy, F: Fn(T, T, T) -> T>(dst: &mut [T], a: &[T], b: &[T], f: F) {
+    dst.iter_mut()
+        .zip(a.iter())
+        .zip(b.iter())
+        .for_each(|((d, a), b)| *d = f(*d, *a, *b));
+}
+
+pub fn fused_scalar<T: Copy, F: Fn(T, T, T) -> T>(dst: &mut [T], a: &[T], s: T, f: F) {
+    dst.iter_mut()
+        .zip(a.iter())
+        .for_each(|(d, a)| *d = f(*d, *a, s));
+}
+
+pub fn gravity<T: Float>(acceleration: [&mut [T]; 2], position: [&[T]; 2], sources: [&[T]; 3], softening2: T) {
+    let ([ax, ay], [x, y], [sx, sy, sm]) = (acceleration, position, sources);
+
+    for (((ax, ay), &x), &y) in ax.iter_mut().zip(ay.iter_mut()).zip(x).zip(y) {
+        for ((&sx, &sy), &m) in sx.iter().zip(sy).zip(sm) {
+            let (dx, dy) = (sx - x, sy - y);
+            let r2 = dx * dx + dy * dy + softening2;
+            let s = (m / (r2 * r2.sqrt())).min(T::max_value());
+            *ax = *ax + dx * s;
+            *ay = *ay + dy * s;
+        }
+    }
+}
@@ -0,0 +1,252 @@
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+pub mod trig;
+
+macro_rules! unary {
+    ($name:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, |$d:ident| $vector:expr, $scalar:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t]) {
+            let len = dst.len();
+            let d_ptr = dst.as_mut_ptr();
+
+            let mut i = 0;
+            while i + $lanes <= len {
+                let $d = $load(d_ptr.add(i));
+                $store(d_ptr.add(i), $vector);
+                i += $lanes;
+            }
+
+            while i < len {
+                let $d = *d_ptr.add(i);
+                *d_ptr.add(i) = $scalar;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! binary {
+    ($name:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, |$d:ident, $a:ident| $vector:expr, $scalar:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t], a: &[$t]) {
+            let len = dst.len().min(a.len());
+            let (d_ptr, a_ptr) = (dst.as_mut_ptr(), a.as_ptr());
+
+            let mut i = 0;
+            while i + $lanes <= len {
+                let $d = $load(d_ptr.add(i));
+                let $a = $load(a_ptr.add(i));
+                $store(d_ptr.add(i), $vector);
+                i += $lanes;
+            }
+
+            while i < len {
+                let $d = *d_ptr.add(i);
+                let $a = *a_ptr.add(i);
+                *d_ptr.add(i) = $scalar;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! scalar {
+    ($name:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, $splat:ident, |$d:ident, $s:ident| $vector:expr, $scalar:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t], s: $t) {
+            let len = dst.len();
+            let d_ptr = dst.as_mut_ptr();
+
+            let mut i = 0;
+            {
+                let $s = $splat(s);
+                while i + $lanes <= len {
+                    let $d = $load(d_ptr.add(i));
+                    $store(d_ptr.add(i), $vector);
+                    i += $lanes;
+                }
+            }
+
+            let $s = s;
+            while i < len {
+                let $d = *d_ptr.add(i);
+                *d_ptr.add(i) = $scalar;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! fused {
+    ($name:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, |$d:ident, $a:ident, $b:ident| $vector:expr, $scalar:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t], a: &[$t], b: &[$t]) {
+            let len = dst.len().min(a.len()).min(b.len());
+            let (d_ptr, a_ptr, b_ptr) = (dst.as_mut_ptr(), a.as_ptr(), b.as_ptr());
+
+            let mut i = 0;
+            while i + $lanes <= len {
+                let $d = $load(d_ptr.add(i));
+                let $a = $load(a_ptr.add(i));
+                let $b = $load(b_ptr.add(i));
+                $store(d_ptr.add(i), $vector);
+                i += $lanes;
+            }
+
+            while i < len {
+                let $d = *d_ptr.add(i);
+                let $a = *a_ptr.add(i);
+                let $b = *b_ptr.add(i);
+                *d_ptr.add(i) = $scalar;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! fused_scalar {
+    ($name:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, $splat:ident, |$d:ident, $a:ident, $s:ident| $vector:expr, $scalar:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t], a: &[$t], s: $t) {
+            let len = dst.len().min(a.len());
+            let (d_ptr, a_ptr) = (dst.as_mut_ptr(), a.as_ptr());
+
+            let mut i = 0;
+            {
+                let $s = $splat(s);
+                while i + $lanes <= len {
+                    let $d = $load(d_ptr.add(i));
+                    let $a = $load(a_ptr.add(i));
+                    $store(d_ptr.add(i), $vector);
+                    i += $lanes;
+                }
+            }
+
+            let $s = s;
+            while i < len {
+                let $d = *d_ptr.add(i);
+                let $a = *a_ptr.add(i);
+                *d_ptr.add(i) = $scalar;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! gravity {
+    ($feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, $splat:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $sqrt:ident, $min:ident) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn gravity(acceleration: [&mut [$t]; 2], position: [&[$t]; 2], sources: [&[$t]; 3], softening2: $t) {
+            let ([ax, ay], [x, y], [sx, sy, sm]) = (acceleration, position, sources);
+            let len = ax.len().min(ay.len()).min(x.len()).min(y.len());
+            let count = sx.len().min(sy.len()).min(sm.len());
+            let (ax_ptr, ay_ptr, x_ptr, y_ptr) = (ax.as_mut_ptr(), ay.as_mut_ptr(), x.as_ptr(), y.as_ptr());
+            let (sx_ptr, sy_ptr, sm_ptr) = (sx.as_ptr(), sy.as_ptr(), sm.as_ptr());
+
+            // the factor m / r^3 is clamped so that a source at the target's position adds 0 rather than NaN
+            let mut i = 0;
+            {
+                let softening2 = $splat(softening2);
+                let max = $splat(<$t>::MAX);
+                while i + $lanes <= len {
+                    let (xi, yi) = ($load(x_ptr.add(i)), $load(y_ptr.add(i)));
+                    let (mut axi, mut ayi) = ($load(ax_ptr.add(i)), $load(ay_ptr.add(i)));
+                    for j in 0..count {
+                        let dx = $sub($splat(*sx_ptr.add(j)), xi);
+                        let dy = $sub($splat(*sy_ptr.add(j)), yi);
+                        let r2 = $add($add($mul(dx, dx), $mul(dy, dy)), softening2);
+                        let s = $min($div($splat(*sm_ptr.add(j)), $mul(r2, $sqrt(r2))), max);
+                        axi = $add(axi, $mul(dx, s));
+                        ayi = $add(ayi, $mul(dy, s));
+                    }
+                    $store(ax_ptr.add(i), axi);
+                    $store(ay_ptr.add(i), ayi);
+                    i += $lanes;
+                }
+            }
+
+            while i < len {
+                let (xi, yi) = (*x_ptr.add(i), *y_ptr.add(i));
+                let (mut axi, mut ayi) = (*ax_ptr.add(i), *ay_ptr.add(i));
+                for j in 0..count {
+                    let dx = *sx_ptr.add(j) - xi;
+                    let dy = *sy_ptr.add(j) - yi;
+                    let r2 = dx * dx + dy * dy + softening2;
+                    let s = (*sm_ptr.add(j) / (r2 * r2.sqrt())).min(<$t>::MAX);
+                    axi += dx * s;
+                    ayi += dy * s;
+                }
+                *ax_ptr.add(i) = axi;
+                *ay_ptr.add(i) = ayi;
+                i += 1;
+            }
+        }
+    };
+}
+
+macro_rules! isa {
+    ($module:ident, $feature:literal, $t:ty, $lanes:expr, $load:ident, $store:ident, $splat:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $sqrt:ident, $andnot:ident, $min:ident) => {
+        pub mod $module {
+            use super::*;
+
+            unary!(sqrt, $feature, $t, $lanes, $load, $store, |d| $sqrt(d), d.sqrt());
+            unary!(abs, $feature, $t, $lanes, $load, $store, |d| $andnot($splat(-0.0), d), d.abs());
+            unary!(recip, $feature, $t, $lanes, $load, $store, |d| $div($splat(1.0), d), 1.0 / d);
+
+            binary!(add, $feature, $t, $lanes, $load, $store, |d, a| $add(d, a), d + a);
+            binary!(sub, $feature, $t, $lanes, $load, $store, |d, a| $sub(d, a), d - a);
+            binary!(mul, $feature, $t, $lanes, $load, $store, |d, a| $mul(d, a), d * a);
+            binary!(div, $feature, $t, $lanes, $load, $store, |d, a| $div(d, a), d / a);
+
+            scalar!(add_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, s| $add(d, s), d + s);
+            scalar!(sub_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, s| $sub(d, s), d - s);
+            scalar!(mul_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, s| $mul(d, s), d * s);
+            scalar!(div_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, s| $div(d, s), d / s);
+
+            fused!(mul_add, $feature, $t, $lanes, $load, $store, |d, a, b| $add(d, $mul(a, b)), d + a * b);
+            fused!(mul_sub, $feature, $t, $lanes, $load, $store, |d, a, b| $sub(d, $mul(a, b)), d - a * b);
+            fused!(div_add, $feature, $t, $lanes, $load, $store, |d, a, b| $add(d, $div(a, b)), d + a / b);
+            fused!(div_sub, $feature, $t, $lanes, $load, $store, |d, a, b| $sub(d, $div(a, b)), d - a / b);
+
+            fused_scalar!(mul_add_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, a, s| $add(d, $mul(a, s)), d + a * s);
+            fused_scalar!(mul_sub_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, a, s| $sub(d, $mul(a, s)), d - a * s);
+            fused_scalar!(div_add_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, a, s| $add(d, $div(a, s)), d + a / s);
+            fused_scalar!(div_sub_scalar, $feature, $t, $lanes, $load, $store, $splat, |d, a, s| $sub(d, $div(a, s)), d - a / s);
+
+            gravity!($feature, $t, $lanes, $load, $store, $splat, $add, $sub, $mul, $div, $sqrt, $min);
+        }
+    };
+}
+
+macro_rules! fma {
+    ($module:ident, $t:ty, $lanes:expr, $load:ident, $store:ident, $splat:ident, $fmadd:ident, $fnmadd:ident) => {
+        pub mod $module {
+            use super::*;
+
+            fused!(mul_add, "avx2,fma", $t, $lanes, $load, $store, |d, a, b| $fmadd(a, b, d), a.mul_add(b, d));
+            fused!(mul_sub, "avx2,fma", $t, $lanes, $load, $store, |d, a, b| $fnmadd(a, b, d), (-a).mul_add(b, d));
+
+            fused_scalar!(mul_add_scalar, "avx2,fma", $t, $lanes, $load, $store, $splat, |d, a, s| $fmadd(a, s, d), a.mul_add(s, d));
+            fused_scalar!(mul_sub_scalar, "avx2,fma", $t, $lanes, $load, $store, $splat, |d, a, s| $fnmadd(a, s, d), (-a).mul_add(s, d));
+        }
+    };
+}
+
+pub mod f32 {
+    use super::*;
+
+    isa!(sse2, "sse2", f32, 4, _mm_loadu_ps, _mm_storeu_ps, _mm_set1_ps, _mm_add_ps, _mm_sub_ps, _mm_mul_ps, _mm_div_ps, _mm_sqrt_ps, _mm_andnot_ps, _mm_min_ps);
+    isa!(avx2, "avx2", f32, 8, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_set1_ps, _mm256_add_ps, _mm256_sub_ps, _mm256_mul_ps, _mm256_div_ps, _mm256_sqrt_ps, _mm256_andnot_ps, _mm256_min_ps);
+    fma!(fma, f32, 8, _mm256_loadu_ps, _mm256_storeu_ps, _mm256_set1_ps, _mm256_fmadd_ps, _mm256_fnmadd_ps);
+}
+
+pub mod f64 {
+    use super::*;
+
+    isa!(sse2, "sse2", f64, 2, _mm_loadu_pd, _mm_storeu_pd, _mm_set1_pd, _mm_add_pd, _mm_sub_pd, _mm_mul_pd, _mm_div_pd, _mm_sqrt_pd, _mm_andnot_pd, _mm_min_pd);
+    isa!(avx2, "avx2", f64, 4, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_set1_pd, _mm256_add_pd, _mm256_sub_pd, _mm256_mul_pd, _mm256_div_pd, _mm256_sqrt_pd, _mm256_andnot_pd, _mm256_min_pd);
+    fma!(fma, f64, 4, _mm256_loadu_pd, _mm256_storeu_pd, _mm256_set1_pd, _mm256_fmadd_pd, _mm256_fnmadd_pd);
+}
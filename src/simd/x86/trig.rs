@@ -0,0 +1,366 @@
+//! Polynomial approximations of `sin`, `cos`, `tan`, `atan` and `atan2`.
+//!
+//! `sin` and `cos` reduce the argument by a multiple of pi using a split Cody-Waite constant
+//! and evaluate the Taylor series of `sin` on [-pi/2, pi/2]; `atan` uses the Cephes range
+//! reduction and rational approximation. Lanes outside `SIN_LIMIT`, and non-finite lanes, are
+//! recomputed with std so the fast path never returns a less accurate special value.
+
+use super::*;
+
+macro_rules! ops {
+    ($feature:literal; $(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? = $body:expr;)*) => {
+        $(
+            #[target_feature(enable = $feature)]
+            #[inline]
+            unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                $body
+            }
+        )*
+    };
+}
+
+macro_rules! unary_kernel {
+    ($name:ident, $feature:literal, $t:ident, $lanes:expr, $fast:ident, $exact:expr, |$x:ident| $ok:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t]) {
+            let mut chunks = dst.chunks_exact_mut($lanes);
+
+            for chunk in &mut chunks {
+                let $x = load(chunk.as_ptr());
+                store(chunk.as_mut_ptr(), checked($fast($x), $x, $ok, $exact));
+            }
+
+            let rest = chunks.into_remainder();
+            if !rest.is_empty() {
+                let mut buf = [1.0; $lanes];
+                buf[..rest.len()].copy_from_slice(rest);
+
+                let $x = load(buf.as_ptr());
+                store(buf.as_mut_ptr(), checked($fast($x), $x, $ok, $exact));
+                rest.copy_from_slice(&buf[..rest.len()]);
+            }
+        }
+    };
+}
+
+macro_rules! binary_kernel {
+    ($name:ident, $feature:literal, $t:ident, $lanes:expr, $fast:ident, $exact:expr, |$y:ident, $x:ident| $ok:expr) => {
+        #[target_feature(enable = $feature)]
+        pub unsafe fn $name(dst: &mut [$t], a: &[$t]) {
+            let len = dst.len().min(a.len());
+            let (dst, a) = (&mut dst[..len], &a[..len]);
+
+            let mut chunks = dst.chunks_exact_mut($lanes);
+            let mut a_chunks = a.chunks_exact($lanes);
+
+            for (chunk, a_chunk) in (&mut chunks).zip(&mut a_chunks) {
+                let ($y, $x) = (load(chunk.as_ptr()), load(a_chunk.as_ptr()));
+                store(chunk.as_mut_ptr(), checked2($fast($y, $x), $y, $x, $ok, $exact));
+            }
+
+            let (rest, a_rest) = (chunks.into_remainder(), a_chunks.remainder());
+            if !rest.is_empty() {
+                let (mut buf, mut a_buf) = ([1.0; $lanes], [1.0; $lanes]);
+                buf[..rest.len()].copy_from_slice(rest);
+                a_buf[..rest.len()].copy_from_slice(a_rest);
+
+                let ($y, $x) = (load(buf.as_ptr()), load(a_buf.as_ptr()));
+                store(buf.as_mut_ptr(), checked2($fast($y, $x), $y, $x, $ok, $exact));
+                rest.copy_from_slice(&buf[..rest.len()]);
+            }
+        }
+    };
+}
+
+macro_rules! trig {
+    ($feature:literal, $t:ident, $lanes:expr) => {
+        const ALL: i32 = (1 << $lanes) - 1;
+
+        ops! { $feature;
+            fn select(m: V, a: V, b: V) -> V = or(and(m, a), andnot(m, b));
+            fn abs(v: V) -> V = andnot(splat(-0.0), v);
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn poly(z: V, coeffs: &[$t]) -> V {
+            let mut p = splat(coeffs[0]);
+            for c in &coeffs[1..] {
+                p = add(mul(p, z), splat(*c));
+            }
+            p
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn reduce(x: V, n: V) -> V {
+            let mut r = x;
+            for part in PI_PARTS.iter() {
+                r = sub(r, mul(n, splat(*part)));
+            }
+            r
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn sin_reduced(r: V) -> V {
+            let z = mul(r, r);
+            let s = add(r, mul(mul(r, z), poly(z, &SIN)));
+            // sin(r) has the sign of r, which the sum loses for r = -0
+            or(abs(s), and(r, splat(-0.0)))
+        }
+
+        // sin(r + n * pi) = (-1)^n * sin(r)
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn sin_v(x: V) -> V {
+            let shifted = add(mul(x, splat(FRAC_1_PI)), splat(MAGIC));
+            let n = sub(shifted, splat(MAGIC));
+            xor(sin_reduced(reduce(x, n)), parity(shifted))
+        }
+
+        // cos(r + (n + 1/2) * pi) = -(-1)^n * sin(r)
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn cos_v(x: V) -> V {
+            let shifted = add(sub(mul(x, splat(FRAC_1_PI)), splat(0.5)), splat(MAGIC));
+            let n = add(sub(shifted, splat(MAGIC)), splat(0.5));
+            xor(xor(sin_reduced(reduce(x, n)), parity(shifted)), splat(-0.0))
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn tan_v(x: V) -> V {
+            div(sin_v(x), cos_v(x))
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn atan_v(x: V) -> V {
+            let sign = and(x, splat(-0.0));
+            let ax = abs(x);
+            let one = splat(1.0);
+
+            let big = gt(ax, splat(TAN_3PI_8));
+            let mid = andnot(big, gt(ax, splat(ATAN_MID)));
+
+            let t = select(big, div(splat(-1.0), ax), select(mid, div(sub(ax, one), add(ax, one)), ax));
+            let offset = select(big, splat(FRAC_PI_2), select(mid, splat(FRAC_PI_4), splat(0.0)));
+            let more = select(big, splat(MOREBITS), select(mid, splat(MOREBITS * 0.5), splat(0.0)));
+
+            let z = mul(t, t);
+            let p = add(t, mul(t, div(mul(z, poly(z, &ATAN_P)), poly(z, &ATAN_Q))));
+            xor(add(offset, add(p, more)), sign)
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn atan2_v(y: V, x: V) -> V {
+            let a = atan_v(div(y, x));
+            let pi = or(splat(PI), and(y, splat(-0.0)));
+            select(lt(x, splat(0.0)), add(a, pi), a)
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn checked(fast: V, x: V, ok: V, exact: fn($t) -> $t) -> V {
+            let ok = mask(ok);
+            if ok == ALL {
+                return fast;
+            }
+
+            let (mut out, mut input) = ([0.0; $lanes], [0.0; $lanes]);
+            store(out.as_mut_ptr(), fast);
+            store(input.as_mut_ptr(), x);
+
+            for i in 0..$lanes {
+                if ok & (1 << i) == 0 {
+                    out[i] = exact(input[i]);
+                }
+            }
+            load(out.as_ptr())
+        }
+
+        #[target_feature(enable = $feature)]
+        #[inline]
+        unsafe fn checked2(fast: V, y: V, x: V, ok: V, exact: fn($t, $t) -> $t) -> V {
+            let ok = mask(ok);
+            if ok == ALL {
+                return fast;
+            }
+
+            let (mut out, mut y_in, mut x_in) = ([0.0; $lanes], [0.0; $lanes], [0.0; $lanes]);
+            store(out.as_mut_ptr(), fast);
+            store(y_in.as_mut_ptr(), y);
+            store(x_in.as_mut_ptr(), x);
+
+            for i in 0..$lanes {
+                if ok & (1 << i) == 0 {
+                    out[i] = exact(y_in[i], x_in[i]);
+                }
+            }
+            load(out.as_ptr())
+        }
+
+        unary_kernel!(sin, $feature, $t, $lanes, sin_v, <$t>::sin, |x| le(abs(x), splat(SIN_LIMIT)));
+        unary_kernel!(cos, $feature, $t, $lanes, cos_v, <$t>::cos, |x| le(abs(x), splat(SIN_LIMIT)));
+        unary_kernel!(tan, $feature, $t, $lanes, tan_v, <$t>::tan, |x| le(abs(x), splat(SIN_LIMIT)));
+        unary_kernel!(atan, $feature, $t, $lanes, atan_v, <$t>::atan, |x| le(abs(x), splat(<$t>::INFINITY)));
+
+        binary_kernel!(atan2, $feature, $t, $lanes, atan2_v, <$t>::atan2, |y, x| {
+            let finite = and(le(abs(y), splat(<$t>::MAX)), le(abs(x), splat(<$t>::MAX)));
+            and(finite, gt(abs(x), splat(0.0)))
+        });
+    };
+}
+
+pub mod f32 {
+    use super::*;
+    use std::f32::consts::*;
+
+    const MAGIC: f32 = 12_582_912.0;
+    const SIN_LIMIT: f32 = 8192.0;
+    const PI_PARTS: [f32; 4] = [3.140625, 0.000_967_025_76, 6.277_114e-7, 1.215_420_1e-10];
+    const SIN: [f32; 6] = [1.605_904_4e-10, -2.505_210_8e-8, 2.755_731_9e-6, -1.984_127e-4, 8.333_333e-3, -1.666_666_7e-1];
+
+    const TAN_3PI_8: f32 = 2.414_213_6;
+    const ATAN_MID: f32 = 0.414_213_57;
+    const MOREBITS: f32 = 0.0;
+    const ATAN_P: [f32; 4] = [8.053_744_5e-2, -1.387_768_6e-1, 1.997_771_1e-1, -3.333_295e-1];
+    const ATAN_Q: [f32; 1] = [1.0];
+
+    pub mod sse2 {
+        use super::*;
+
+        type V = __m128;
+
+        ops! { "sse2";
+            fn load(p: *const f32) -> V = _mm_loadu_ps(p);
+            fn store(p: *mut f32, v: V) = _mm_storeu_ps(p, v);
+            fn splat(s: f32) -> V = _mm_set1_ps(s);
+            fn add(a: V, b: V) -> V = _mm_add_ps(a, b);
+            fn sub(a: V, b: V) -> V = _mm_sub_ps(a, b);
+            fn mul(a: V, b: V) -> V = _mm_mul_ps(a, b);
+            fn div(a: V, b: V) -> V = _mm_div_ps(a, b);
+            fn and(a: V, b: V) -> V = _mm_and_ps(a, b);
+            fn andnot(a: V, b: V) -> V = _mm_andnot_ps(a, b);
+            fn or(a: V, b: V) -> V = _mm_or_ps(a, b);
+            fn xor(a: V, b: V) -> V = _mm_xor_ps(a, b);
+            fn lt(a: V, b: V) -> V = _mm_cmplt_ps(a, b);
+            fn le(a: V, b: V) -> V = _mm_cmple_ps(a, b);
+            fn gt(a: V, b: V) -> V = _mm_cmpgt_ps(a, b);
+            fn mask(v: V) -> i32 = _mm_movemask_ps(v);
+            fn parity(v: V) -> V = _mm_castsi128_ps(_mm_slli_epi32(_mm_castps_si128(v), 31));
+        }
+
+        trig!("sse2", f32, 4);
+    }
+
+    pub mod avx2 {
+        use super::*;
+
+        type V = __m256;
+
+        ops! { "avx2";
+            fn load(p: *const f32) -> V = _mm256_loadu_ps(p);
+            fn store(p: *mut f32, v: V) = _mm256_storeu_ps(p, v);
+            fn splat(s: f32) -> V = _mm256_set1_ps(s);
+            fn add(a: V, b: V) -> V = _mm256_add_ps(a, b);
+            fn sub(a: V, b: V) -> V = _mm256_sub_ps(a, b);
+            fn mul(a: V, b: V) -> V = _mm256_mul_ps(a, b);
+            fn div(a: V, b: V) -> V = _mm256_div_ps(a, b);
+            fn and(a: V, b: V) -> V = _mm256_and_ps(a, b);
+            fn andnot(a: V, b: V) -> V = _mm256_andnot_ps(a, b);
+            fn or(a: V, b: V) -> V = _mm256_or_ps(a, b);
+            fn xor(a: V, b: V) -> V = _mm256_xor_ps(a, b);
+            fn lt(a: V, b: V) -> V = _mm256_cmp_ps(a, b, _CMP_LT_OQ);
+            fn le(a: V, b: V) -> V = _mm256_cmp_ps(a, b, _CMP_LE_OQ);
+            fn gt(a: V, b: V) -> V = _mm256_cmp_ps(a, b, _CMP_GT_OQ);
+            fn mask(v: V) -> i32 = _mm256_movemask_ps(v);
+            fn parity(v: V) -> V = _mm256_castsi256_ps(_mm256_slli_epi32(_mm256_castps_si256(v), 31));
+        }
+
+        trig!("avx2", f32, 8);
+    }
+}
+
+pub mod f64 {
+    use super::*;
+    use std::f64::consts::*;
+
+    const MAGIC: f64 = 6_755_399_441_055_744.0;
+    const SIN_LIMIT: f64 = 1e6;
+    const PI_PARTS: [f64; 3] = [3.141_592_653_468_251_2, 1.215_420_101_260_793_2e-10, 4.044_532_497_591_901e-21];
+    const SIN: [f64; 11] = [
+        -3.868_170_170_630_684e-23, 1.957_294_106_339_126_3e-20, -8.220_635_246_624_33e-18,
+        2.811_457_254_345_520_6e-15, -7.647_163_731_819_816e-13, 1.605_904_383_682_161_3e-10,
+        -2.505_210_838_544_172e-8, 2.755_731_922_398_589_3e-6, -1.984_126_984_126_984e-4,
+        8.333_333_333_333_333e-3, -1.666_666_666_666_666_6e-1,
+    ];
+
+    const TAN_3PI_8: f64 = 2.414_213_562_373_095;
+    const ATAN_MID: f64 = 0.66;
+    const MOREBITS: f64 = 6.123_233_995_736_766e-17;
+    const ATAN_P: [f64; 5] = [
+        -8.750_608_600_031_904e-1, -1.615_753_718_733_365e1, -7.500_855_792_314_705e1,
+        -1.228_866_684_490_136_2e2, -6.485_021_904_942_025e1,
+    ];
+    const ATAN_Q: [f64; 6] = [
+        1.0, 2.485_846_490_142_306_3e1, 1.650_270_098_316_988_6e2,
+        4.328_810_604_912_903e2, 4.853_903_996_359_137e2, 1.945_506_571_482_614e2,
+    ];
+
+    pub mod sse2 {
+        use super::*;
+
+        type V = __m128d;
+
+        ops! { "sse2";
+            fn load(p: *const f64) -> V = _mm_loadu_pd(p);
+            fn store(p: *mut f64, v: V) = _mm_storeu_pd(p, v);
+            fn splat(s: f64) -> V = _mm_set1_pd(s);
+            fn add(a: V, b: V) -> V = _mm_add_pd(a, b);
+            fn sub(a: V, b: V) -> V = _mm_sub_pd(a, b);
+            fn mul(a: V, b: V) -> V = _mm_mul_pd(a, b);
+            fn div(a: V, b: V) -> V = _mm_div_pd(a, b);
+            fn and(a: V, b: V) -> V = _mm_and_pd(a, b);
+            fn andnot(a: V, b: V) -> V = _mm_andnot_pd(a, b);
+            fn or(a: V, b: V) -> V = _mm_or_pd(a, b);
+            fn xor(a: V, b: V) -> V = _mm_xor_pd(a, b);
+            fn lt(a: V, b: V) -> V = _mm_cmplt_pd(a, b);
+            fn le(a: V, b: V) -> V = _mm_cmple_pd(a, b);
+            fn gt(a: V, b: V) -> V = _mm_cmpgt_pd(a, b);
+            fn mask(v: V) -> i32 = _mm_movemask_pd(v);
+            fn parity(v: V) -> V = _mm_castsi128_pd(_mm_slli_epi64(_mm_castpd_si128(v), 63));
+        }
+
+        trig!("sse2", f64, 2);
+    }
+
+    pub mod avx2 {
+        use super::*;
+
+        type V = __m256d;
+
+        ops! { "avx2";
+            fn load(p: *const f64) -> V = _mm256_loadu_pd(p);
+            fn store(p: *mut f64, v: V) = _mm256_storeu_pd(p, v);
+            fn splat(s: f64) -> V = _mm256_set1_pd(s);
+            fn add(a: V, b: V) -> V = _mm256_add_pd(a, b);
+            fn sub(a: V, b: V) -> V = _mm256_sub_pd(a, b);
+            fn mul(a: V, b: V) -> V = _mm256_mul_pd(a, b);
+            fn div(a: V, b: V) -> V = _mm256_div_pd(a, b);
+            fn and(a: V, b: V) -> V = _mm256_and_pd(a, b);
+            fn andnot(a: V, b: V) -> V = _mm256_andnot_pd(a, b);
+            fn or(a: V, b: V) -> V = _mm256_or_pd(a, b);
+            fn xor(a: V, b: V) -> V = _mm256_xor_pd(a, b);
+            fn lt(a: V, b: V) -> V = _mm256_cmp_pd(a, b, _CMP_LT_OQ);
+            fn le(a: V, b: V) -> V = _mm256_cmp_pd(a, b, _CMP_LE_OQ);
+            fn gt(a: V, b: V) -> V = _mm256_cmp_pd(a, b, _CMP_GT_OQ);
+            fn mask(v: V) -> i32 = _mm256_movemask_pd(v);
+            fn parity(v: V) -> V = _mm256_castsi256_pd(_mm256_slli_epi64(_mm256_castpd_si256(v), 63));
+        }
+
+        trig!("avx2", f64, 4);
+    }
+}
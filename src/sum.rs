@@ -0,0 +1,145 @@
+//! Summation orders shared by the serial, SIMD and parallel reductions.
+//!
+//! The deterministic sum splits the input into fixed `BLOCK`-sized blocks, sums each block
+//! with a fixed `LANES`-wide layout and combines the block sums pairwise. The reduction tree
+//! depends only on the input length, so any implementation that sums the same blocks and
+//! combines them with `pairwise` produces bit-identical results.
+
+use std::ops::{Add, Sub};
+
+pub(crate) const BLOCK: usize = 1024;
+
+const LANES: usize = 8;
+
+const PAIRWISE_BASE: usize = 32;
+
+pub(crate) fn kahan<T: Copy + Default + Add<T, Output=T> + Sub<T, Output=T>>(values: &[T]) -> T {
+    let mut sum = T::default();
+    let mut compensation = T::default();
+
+    for v in values {
+        let y = *v - compensation;
+        let t = sum + y;
+        compensation = (t - sum) - y;
+        sum = t;
+    }
+
+    sum
+}
+
+pub(crate) fn pairwise<T: Copy + Default + Add<T, Output=T>>(values: &[T]) -> T {
+    if values.len() <= PAIRWISE_BASE {
+        values.iter().fold(T::default(), |sum, v| sum + *v)
+    } else {
+        let (a, b) = values.split_at(values.len() / 2);
+        pairwise(a) + pairwise(b)
+    }
+}
+
+pub(crate) fn block<T: Copy + Default + Add<T, Output=T>>(values: &[T]) -> T {
+    debug_assert!(values.len() <= BLOCK);
+
+    let mut lanes = [T::default(); LANES];
+
+    let chunks = values.chunks_exact(LANES);
+    let remainder = chunks.remainder();
+
+    chunks.for_each(|chunk| {
+        lanes.iter_mut()
+            .zip(chunk.iter())
+            .for_each(|(lane, v)| *lane = *lane + *v);
+    });
+
+    lanes.iter_mut()
+        .zip(remainder.iter())
+        .for_each(|(lane, v)| *lane = *lane + *v);
+
+    pairwise(&lanes)
+}
+
+pub(crate) fn deterministic<T: Copy + Default + Add<T, Output=T>>(values: &[T]) -> T {
+    let blocks = values.chunks(BLOCK)
+        .map(block)
+        .collect::<Vec<_>>();
+
+    pairwise(&blocks)
+}
+
+/// Same tree as `pairwise`, with the halves summed in parallel above the threshold.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_pairwise<T: Copy + Default + Add<T, Output=T> + Send + Sync>(values: &[T]) -> T {
+    if values.len() <= crate::par::threshold().max(PAIRWISE_BASE) {
+        pairwise(values)
+    } else {
+        let (a, b) = values.split_at(values.len() / 2);
+        let (a, b) = rayon::join(|| par_pairwise(a), || par_pairwise(b));
+        a + b
+    }
+}
+
+/// Same blocks as `deterministic`, with the blocks summed in parallel above the threshold.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_deterministic<T: Copy + Default + Add<T, Output=T> + Send + Sync>(values: &[T]) -> T {
+    use rayon::prelude::*;
+
+    if values.len() <= crate::par::threshold() {
+        return deterministic(values);
+    }
+
+    let blocks = values.par_chunks(BLOCK)
+        .map(block)
+        .collect::<Vec<_>>();
+
+    par_pairwise(&blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kahan_recovers_small_terms() {
+        let mut values = vec![1.0f64];
+        values.extend(std::iter::repeat_n(1e-16, 10_000));
+
+        let naive: f64 = values.iter().sum();
+
+        assert_eq!(1.0, naive);
+        assert!((kahan(&values) - (1.0 + 1e-12)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn pairwise_matches_exact_integer_sum() {
+        let values = (0..1000).map(|i| i as f64).collect::<Vec<_>>();
+
+        assert_eq!(499_500.0, pairwise(&values));
+    }
+
+    #[test]
+    fn deterministic_is_independent_of_how_blocks_are_split() {
+        let values = (0..(3 * BLOCK + 17))
+            .map(|i| ((i * 7919) % 1000) as f32 * 0.001 + 1e-4)
+            .collect::<Vec<_>>();
+
+        let expected = deterministic(&values);
+
+        // summing the blocks on separate "threads" and combining them gives the same bits
+        let (first, second) = values.split_at(2 * BLOCK);
+        let mut blocks = first.chunks(BLOCK).map(block).collect::<Vec<_>>();
+        blocks.extend(second.chunks(BLOCK).map(block));
+
+        assert_eq!(expected.to_bits(), pairwise(&blocks).to_bits());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_sums_match_serial_bits() {
+        let values = (0..(100 * BLOCK + 17))
+            .map(|i| ((i * 7919) % 1000) as f32 * 0.001 + 1e-4)
+            .collect::<Vec<_>>();
+
+        assert!(values.len() > crate::par::threshold());
+        assert_eq!(deterministic(&values).to_bits(), par_deterministic(&values).to_bits());
+        assert_eq!(pairwise(&values).to_bits(), par_pairwise(&values).to_bits());
+    }
+}
@@ -0,0 +1,236 @@
+//! The methods shared by `VecN`, `Vec2` and `Vec3`, written over arrays of column references so that
+//! the named vectors can pass their fields without being laid out as an array.
+//!
+//! Columns are public and can drift apart in length. Lookups and removals then return `None`, inserts
+//! return `OutOfBounds` and `retain` keeps every row, without touching any column, while the zips
+//! panic as for mismatched operands.
+
+use super::*;
+use std::ops::Deref;
+
+fn len<T, C: Deref<Target=Vec1<T>>>(columns: &[C]) -> usize {
+    columns.first().map_or(0, |column| column.len())
+}
+
+/// The length of the columns if they all have the same length.
+fn equal_len<T, C: Deref<Target=Vec1<T>>>(columns: &[C]) -> Option<usize> {
+    let len = len(columns);
+
+    if columns.iter().all(|column| column.len() == len) {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+fn transpose<U, const N: usize>(row: [Option<U>; N]) -> Option<[U; N]> {
+    if row.iter().all(Option::is_some) {
+        Some(row.map(Option::unwrap))
+    } else {
+        None
+    }
+}
+
+fn push<T, const N: usize>(columns: &mut [&mut Vec1<T>; N], values: [T; N]) {
+    columns.iter_mut()
+        .zip(values)
+        .for_each(|(column, value)| column.values.push(value));
+}
+
+pub(super) fn get<T, const N: usize>(columns: [&Vec1<T>; N], index: usize) -> Option<[&T; N]> {
+    transpose(columns.map(|column| column.values.get(index)))
+}
+
+pub(super) fn get_mut<T, const N: usize>(columns: [&mut Vec1<T>; N], index: usize) -> Option<[&mut T; N]> {
+    transpose(columns.map(|column| column.values.get_mut(index)))
+}
+
+pub(super) fn insert<T, const N: usize>(mut columns: [&mut Vec1<T>; N], values: [T; N], index: usize) -> InsertOutcome<[T; N]> {
+    match equal_len(&columns) {
+        Some(len) if index < len => {
+            let mut values = IntoIterator::into_iter(values);
+            InsertOutcome::Replaced(columns.map(|column| std::mem::replace(&mut column.values[index], values.next().unwrap())))
+        }
+        Some(len) if index == len => {
+            push(&mut columns, values);
+            InsertOutcome::Appended
+        }
+        _ => InsertOutcome::OutOfBounds,
+    }
+}
+
+pub(super) fn insert_or_resize_with<T, F: FnMut() -> [T; N], const N: usize>(mut columns: [&mut Vec1<T>; N], values: [T; N], index: usize, mut f: F) -> InsertOutcome<[T; N]> {
+    if let Some(len) = equal_len(&columns) {
+        for _ in len..index {
+            push(&mut columns, f());
+        }
+    }
+
+    insert(columns, values, index)
+}
+
+pub(super) fn swap_remove<T, const N: usize>(columns: [&mut Vec1<T>; N], index: usize) -> Option<SwapRemoved<[T; N]>> {
+    let last = equal_len(&columns).filter(|len| index < *len)? - 1;
+    let value = columns.map(|column| column.values.swap_remove(index));
+    let moved_from = if index == last { None } else { Some(last) };
+
+    Some(SwapRemoved { value, moved_from })
+}
+
+pub(super) fn remove<T, const N: usize>(columns: [&mut Vec1<T>; N], index: usize) -> Option<[T; N]> {
+    equal_len(&columns).filter(|len| index < *len)?;
+
+    Some(columns.map(|column| column.values.remove(index)))
+}
+
+pub(super) fn retain<T, F: FnMut([&T; N]) -> bool, const N: usize>(columns: [&mut Vec1<T>; N], f: F) {
+    if equal_len(&columns).is_none() {
+        return;
+    }
+
+    let mask = rows(columns.each_ref().map(|column| &**column))
+        .map(f)
+        .collect::<Vec<_>>();
+
+    retain_by_mask(columns, &mask);
+}
+
+pub(super) fn retain_by_mask<T, const N: usize>(columns: [&mut Vec1<T>; N], mask: &[bool]) {
+    IntoIterator::into_iter(columns).for_each(|column| column.retain_by_mask(mask));
+}
+
+pub(super) fn drain<'a, T, R: RangeBounds<usize> + Clone, const N: usize>(columns: [&'a mut Vec1<T>; N], range: R) -> impl Iterator<Item=[T; N]> + 'a {
+    let mut drains = columns.map(|column| column.drain(range.clone()));
+
+    std::iter::from_fn(move || transpose(drains.each_mut().map(|drain| drain.next())))
+}
+
+/// Stops at the end of the shortest column.
+pub(super) fn rows<'a, T, const N: usize>(columns: [&'a Vec1<T>; N]) -> impl Iterator<Item=[&'a T; N]> + 'a {
+    let mut columns = columns.map(|column| column.values.iter());
+
+    std::iter::from_fn(move || transpose(columns.each_mut().map(|column| column.next())))
+}
+
+/// Stops at the end of the shortest column.
+pub(super) fn rows_mut<'a, T, const N: usize>(columns: [&'a mut Vec1<T>; N]) -> impl Iterator<Item=[&'a mut T; N]> + 'a {
+    let mut columns = columns.map(|column| column.values.iter_mut());
+
+    std::iter::from_fn(move || transpose(columns.each_mut().map(|column| column.next())))
+}
+
+pub(super) fn zip_to_value<T, T2: Copy, F: Fn(&mut T, T2), const N: usize>(columns: [&mut Vec1<T>; N], rhs: T2, f: F) {
+    IntoIterator::into_iter(columns).for_each(|column| column.zip_to_value(rhs, &f));
+}
+
+pub(super) fn zip_to_vec1<T, T2: Copy, F: Fn(&mut T, T2), const N: usize>(columns: [&mut Vec1<T>; N], rhs: &Vec1<T2>, f: F) {
+    assert_len!(len(&columns), rhs.len());
+
+    IntoIterator::into_iter(columns).for_each(|column| column.zip_to_vec1(rhs, &f));
+}
+
+pub(super) fn zip_to_columns<T, T2: Copy, F: Fn(&mut T, T2), const N: usize>(columns: [&mut Vec1<T>; N], rhs: [&Vec1<T2>; N], f: F) {
+    assert_len!(len(&columns), len(&rhs));
+
+    IntoIterator::into_iter(columns)
+        .zip(rhs)
+        .for_each(|(column, rhs)| column.zip_to_vec1(rhs, &f));
+}
+
+pub(super) fn zip_to_columns_and_value<T, T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3), const N: usize>(columns: [&mut Vec1<T>; N], a: [&Vec1<T2>; N], value: T3, f: F) {
+    assert_len!(len(&columns), len(&a));
+
+    IntoIterator::into_iter(columns)
+        .zip(a)
+        .for_each(|(column, a)| column.zip_to_vec1_and_value(a, value, &f));
+}
+
+pub(super) fn zip_to_columns_and_vec1<T, T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3), const N: usize>(columns: [&mut Vec1<T>; N], a: [&Vec1<T2>; N], vec1: &Vec1<T3>, f: F) {
+    assert_len!(len(&columns), len(&a));
+    assert_len!(len(&columns), vec1.len());
+
+    IntoIterator::into_iter(columns)
+        .zip(a)
+        .for_each(|(column, a)| column.zip_to_vec1_and_vec1(a, vec1, &f));
+}
+
+pub(super) fn zip_rows_to_value<T, T2: Copy, F: Fn([&mut T; N], T2), const N: usize>(columns: [&mut Vec1<T>; N], rhs: T2, f: F) {
+    rows_mut(columns).for_each(|row| f(row, rhs));
+}
+
+pub(super) fn zip_rows_to_vec1<T, T2: Copy, F: Fn([&mut T; N], T2), const N: usize>(columns: [&mut Vec1<T>; N], rhs: &Vec1<T2>, f: F) {
+    assert_len!(len(&columns), rhs.len());
+
+    rows_mut(columns)
+        .zip(rhs.iter())
+        .for_each(|(row, r)| f(row, *r));
+}
+
+pub(super) fn zip_rows_to_columns<T, T2: Copy, F: Fn([&mut T; N], [T2; N]), const N: usize>(columns: [&mut Vec1<T>; N], rhs: [&Vec1<T2>; N], f: F) {
+    assert_len!(len(&columns), len(&rhs));
+
+    rows_mut(columns)
+        .zip(rows(rhs))
+        .for_each(|(row, r)| f(row, r.map(|r| *r)));
+}
+
+pub(super) fn zip_rows_to_columns_and_value<T, T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3), const N: usize>(columns: [&mut Vec1<T>; N], a: [&Vec1<T2>; N], value: T3, f: F) {
+    assert_len!(len(&columns), len(&a));
+
+    rows_mut(columns)
+        .zip(rows(a))
+        .for_each(|(row, a)| f(row, a.map(|a| *a), value));
+}
+
+pub(super) fn zip_rows_to_columns_and_vec1<T, T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3), const N: usize>(columns: [&mut Vec1<T>; N], a: [&Vec1<T2>; N], vec1: &Vec1<T3>, f: F) {
+    assert_len!(len(&columns), len(&a));
+    assert_len!(len(&columns), vec1.len());
+
+    rows_mut(columns)
+        .zip(rows(a))
+        .zip(vec1.iter())
+        .for_each(|((row, a), v)| f(row, a.map(|a| *a), *v));
+}
+
+pub(super) fn zip_to_expr<T, E: VExpr, F: Fn(&mut T, E::Output), const N: usize>(columns: [&mut Vec1<T>; N], expr: &E, f: F) {
+    assert_len!(Some(len(&columns)), expr.size());
+
+    rows_mut(columns)
+        .enumerate()
+        .for_each(|(i, row)| {
+            for (axis, value) in IntoIterator::into_iter(row).enumerate() {
+                f(value, expr.get(i, axis));
+            }
+        });
+}
+
+pub(super) fn chunked_zip_to_expr<T: Element, E: VExpr + Element, F: Fn(&mut T, E::Output) + Sync, const N: usize>(columns: [&mut Vec1<T>; N], expr: &E, f: F) {
+    assert_len!(Some(len(&columns)), expr.size());
+
+    IntoIterator::into_iter(columns)
+        .enumerate()
+        .for_each(|(axis, column)| column.chunked_zip_column_to_expr(axis, expr, &f));
+}
+
+pub(super) fn assign_columns<T, E: VExpr<Output=T>, const N: usize>(columns: [&mut Vec1<T>; N], expr: &E) {
+    let len = expr.size().unwrap_or(0);
+
+    IntoIterator::into_iter(columns)
+        .enumerate()
+        .for_each(|(axis, column)| {
+            column.values.clear();
+            column.values.extend((0..len).map(|i| expr.get(i, axis)));
+        });
+}
+
+// column availability and element types are the same for every axis,
+// so either all columns take the SIMD path or none of them do
+pub(super) fn fused_assign<T: 'static, A: VExpr, B: VExpr, const N: usize>(columns: [&mut Vec1<T>; N], op: FusedOp, a: &A, b: &B) -> bool
+    where
+        A::Output: 'static,
+        B::Output: 'static,
+{
+    IntoIterator::into_iter(columns)
+        .enumerate()
+        .all(|(axis, column)| column.fused_assign_column(op, axis, a, b))
+}
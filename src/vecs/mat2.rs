@@ -0,0 +1,389 @@
+use super::*;
+use std::iter::FromIterator;
+
+/// A single 2x2 matrix applied to every element, `x' = xx * x + xy * y` and `y' = yx * x + yy * y`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UniformMat2<T> {
+    pub xx: T,
+    pub xy: T,
+    pub yx: T,
+    pub yy: T,
+}
+
+impl<T: Float> UniformMat2<T> {
+    pub fn identity() -> Self {
+        UniformMat2 {
+            xx: T::one(),
+            xy: T::zero(),
+            yx: T::zero(),
+            yy: T::one(),
+        }
+    }
+
+    /// A counter-clockwise rotation by `angle` in radians.
+    pub fn from_angle(angle: T) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        UniformMat2 {
+            xx: cos,
+            xy: -sin,
+            yx: sin,
+            yy: cos,
+        }
+    }
+
+    pub fn determinant(&self) -> T {
+        self.xx * self.yy - self.xy * self.yx
+    }
+
+    /// A singular matrix gives infinite or NaN entries.
+    pub fn inverse(&self) -> Self {
+        let det = self.determinant();
+
+        UniformMat2 {
+            xx: self.yy / det,
+            xy: -self.xy / det,
+            yx: -self.yx / det,
+            yy: self.xx / det,
+        }
+    }
+
+    /// The matrix that applies `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        UniformMat2 {
+            xx: self.xx * other.xx + self.xy * other.yx,
+            xy: self.xx * other.xy + self.xy * other.yy,
+            yx: self.yx * other.xx + self.yy * other.yx,
+            yy: self.yx * other.xy + self.yy * other.yy,
+        }
+    }
+
+    pub fn transform(&self, x: T, y: T) -> (T, T) {
+        (self.xx * x + self.xy * y, self.yx * x + self.yy * y)
+    }
+
+    pub fn apply(&self, target: &mut Vec2<T>) {
+        target.x.iter_mut()
+            .zip(target.y.iter_mut())
+            .for_each(|(x, y)| {
+                let (x1, y1) = self.transform(*x, *y);
+                *x = x1;
+                *y = y1;
+            });
+    }
+}
+
+/// A single affine transform applied to every element, the linear part followed by the translation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UniformAffine2<T> {
+    pub linear: UniformMat2<T>,
+    pub translation: (T, T),
+}
+
+impl<T: Float> UniformAffine2<T> {
+    pub fn determinant(&self) -> T {
+        self.linear.determinant()
+    }
+
+    pub fn inverse(&self) -> Self {
+        let linear = self.linear.inverse();
+        let (x, y) = linear.transform(self.translation.0, self.translation.1);
+
+        UniformAffine2 {
+            linear,
+            translation: (-x, -y),
+        }
+    }
+
+    /// The transform that applies `other` and then `self`.
+    pub fn compose(&self, other: &Self) -> Self {
+        let (x, y) = self.transform(other.translation.0, other.translation.1);
+
+        UniformAffine2 {
+            linear: self.linear.compose(&other.linear),
+            translation: (x, y),
+        }
+    }
+
+    pub fn transform(&self, x: T, y: T) -> (T, T) {
+        let (x, y) = self.linear.transform(x, y);
+        (x + self.translation.0, y + self.translation.1)
+    }
+
+    pub fn apply(&self, target: &mut Vec2<T>) {
+        target.x.iter_mut()
+            .zip(target.y.iter_mut())
+            .for_each(|(x, y)| {
+                let (x1, y1) = self.transform(*x, *y);
+                *x = x1;
+                *y = y1;
+            });
+    }
+}
+
+/// One 2x2 matrix per element, stored as four columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mat2<T> {
+    pub xx: Vec1<T>,
+    pub xy: Vec1<T>,
+    pub yx: Vec1<T>,
+    pub yy: Vec1<T>,
+}
+
+impl<T> Default for Mat2<T> {
+    fn default() -> Self {
+        Mat2 {
+            xx: Vec1::default(),
+            xy: Vec1::default(),
+            yx: Vec1::default(),
+            yy: Vec1::default(),
+        }
+    }
+}
+
+impl<T> Mat2<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        assert_len!(self.xx.len(), self.xy.len());
+        assert_len!(self.xx.len(), self.yx.len());
+        assert_len!(self.xx.len(), self.yy.len());
+        self.xx.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Float> Mat2<T> {
+    pub fn identity(len: usize) -> Self {
+        Self::from_uniform(UniformMat2::identity(), len)
+    }
+
+    pub fn from_uniform(matrix: UniformMat2<T>, len: usize) -> Self {
+        Mat2 {
+            xx: vec![matrix.xx; len].into(),
+            xy: vec![matrix.xy; len].into(),
+            yx: vec![matrix.yx; len].into(),
+            yy: vec![matrix.yy; len].into(),
+        }
+    }
+
+    /// Counter-clockwise rotations by `angles` in radians.
+    pub fn from_angles(angles: &Vec1<T>) -> Self {
+        angles.iter()
+            .map(|a| UniformMat2::from_angle(*a))
+            .collect()
+    }
+
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<UniformMat2<T>> {
+        let index = index.index();
+
+        Some(UniformMat2 {
+            xx: *self.xx.get(index)?,
+            xy: *self.xy.get(index)?,
+            yx: *self.yx.get(index)?,
+            yy: *self.yy.get(index)?,
+        })
+    }
+
+    pub fn determinant(&self) -> Vec1<T> {
+        Vec1 {
+            values: self.iter().map(|m| m.determinant()).collect(),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.iter()
+            .map(|m| m.inverse())
+            .collect()
+    }
+
+    /// The matrices that apply `other` and then `self`, element by element.
+    pub fn compose(&self, other: &Self) -> Self {
+        assert_len!(self.len(), other.len());
+
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.compose(&b))
+            .collect()
+    }
+
+    pub fn apply(&self, target: &mut Vec2<T>) {
+        assert_len!(self.len(), target.len());
+
+        target.x.iter_mut()
+            .zip(target.y.iter_mut())
+            .zip(self.iter())
+            .for_each(|((x, y), m)| {
+                let (x1, y1) = m.transform(*x, *y);
+                *x = x1;
+                *y = y1;
+            });
+    }
+
+    fn iter(&self) -> impl Iterator<Item=UniformMat2<T>> + '_ {
+        self.xx.iter()
+            .zip(self.xy.iter())
+            .zip(self.yx.iter())
+            .zip(self.yy.iter())
+            .map(|(((xx, xy), yx), yy)| UniformMat2 { xx: *xx, xy: *xy, yx: *yx, yy: *yy })
+    }
+}
+
+impl<T> FromIterator<UniformMat2<T>> for Mat2<T> {
+    fn from_iter<I: IntoIterator<Item=UniformMat2<T>>>(iter: I) -> Self {
+        let mut result = Mat2::new();
+
+        for m in iter {
+            result.xx.values.push(m.xx);
+            result.xy.values.push(m.xy);
+            result.yx.values.push(m.yx);
+            result.yy.values.push(m.yy);
+        }
+
+        result
+    }
+}
+
+/// One affine transform per element, the linear part followed by the translation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Affine2<T> {
+    pub linear: Mat2<T>,
+    pub translation: Vec2<T>,
+}
+
+impl<T> Default for Affine2<T> {
+    fn default() -> Self {
+        Affine2 {
+            linear: Mat2::default(),
+            translation: Vec2::default(),
+        }
+    }
+}
+
+impl<T> Affine2<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        assert_len!(self.linear.len(), self.translation.len());
+        self.linear.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Float + Scalar + AddAssign<T> + MulAssign<T> + Element + 'static> Affine2<T> {
+    pub fn from_uniform(transform: UniformAffine2<T>, len: usize) -> Self {
+        Affine2 {
+            linear: Mat2::from_uniform(transform.linear, len),
+            translation: Vec2 {
+                x: vec![transform.translation.0; len].into(),
+                y: vec![transform.translation.1; len].into(),
+            },
+        }
+    }
+
+    pub fn determinant(&self) -> Vec1<T> {
+        self.linear.determinant()
+    }
+
+    pub fn inverse(&self) -> Self {
+        let linear = self.linear.inverse();
+
+        let mut translation = self.translation.clone();
+        linear.apply(&mut translation);
+        translation *= -T::one();
+
+        Affine2 { linear, translation }
+    }
+
+    /// The transforms that apply `other` and then `self`, element by element.
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut translation = other.translation.clone();
+        self.apply(&mut translation);
+
+        Affine2 {
+            linear: self.linear.compose(&other.linear),
+            translation,
+        }
+    }
+
+    pub fn apply(&self, target: &mut Vec2<T>) {
+        self.linear.apply(target);
+        *target += &self.translation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn vec2(x: Vec<f64>, y: Vec<f64>) -> Vec2<f64> {
+        Vec2 { x: x.into(), y: y.into() }
+    }
+
+    fn scale(sx: f64, sy: f64) -> UniformMat2<f64> {
+        UniformMat2 { xx: sx, xy: 0.0, yx: 0.0, yy: sy }
+    }
+
+    #[test]
+    fn per_element_apply() {
+        let m: Mat2<f64> = vec![scale(2.0, 3.0), scale(-1.0, 0.5)].into_iter().collect();
+        let mut v = vec2(vec![1.0, 4.0], vec![1.0, 4.0]);
+
+        m.apply(&mut v);
+
+        assert_eq!(vec2(vec![2.0, -4.0], vec![3.0, 2.0]), v);
+        assert_eq!(vec![6.0, -0.5], m.determinant().values);
+    }
+
+    #[test]
+    fn rotation_inverse_and_compose() {
+        let m = Mat2::from_angles(&vec![FRAC_PI_2, 0.3].into());
+        let identity = m.compose(&m.inverse());
+
+        for (a, b) in identity.iter().zip(Mat2::<f64>::identity(2).iter()) {
+            assert!((a.xx - b.xx).abs() < 1e-15 && (a.xy - b.xy).abs() < 1e-15);
+            assert!((a.yx - b.yx).abs() < 1e-15 && (a.yy - b.yy).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn affine_matches_uniform() {
+        let quarter_turn = UniformMat2 { xx: 0.0, xy: -1.0, yx: 1.0, yy: 0.0 };
+        let first = UniformAffine2 { linear: quarter_turn, translation: (1.0, 2.0) };
+        let second = UniformAffine2 { linear: scale(2.0, 4.0), translation: (-1.0, 0.5) };
+
+        let composed = second.compose(&first);
+        let per_element = Affine2::from_uniform(second, 2).compose(&Affine2::from_uniform(first, 2));
+
+        let mut a = vec2(vec![1.0, -3.0], vec![0.0, 2.0]);
+        let mut b = a.clone();
+        let mut c = a.clone();
+
+        composed.apply(&mut a);
+        per_element.apply(&mut b);
+        first.apply(&mut c);
+        second.apply(&mut c);
+
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(vec![8.0, 8.0], per_element.determinant().values);
+
+        composed.inverse().apply(&mut a);
+        per_element.inverse().apply(&mut b);
+        for (a, e) in a.x.iter().chain(a.y.iter()).zip([1.0, -3.0, 0.0, 2.0].iter()) {
+            assert!((a - e).abs() < 1e-15);
+        }
+        assert_eq!(a, b);
+    }
+}
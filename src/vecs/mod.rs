@@ -1,12 +1,200 @@
+use crate::error::{check_size, SimdVecError};
 use crate::ops::*;
-use num_traits::Float;
+use crate::simd::{self, BinaryOp, FusedOp, ScalarOp, UnaryOp};
+use crate::sum;
+#[cfg(feature = "rayon")]
+use crate::par;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::ops::RangeBounds;
+use num_traits::{Float, FromPrimitive};
 use std::ops::*;
 
-pub use vec1::Vec1;
-pub use vec2::Vec2;
+/// Types that can index the columns of a vector, such as `usize` or a validated `id::Valid`.
+pub trait VecIndex {
+    fn index(&self) -> usize;
+}
+
+impl VecIndex for usize {
+    fn index(&self) -> usize {
+        *self
+    }
+}
+
+/// The element types that operators may split across threads: `Send + Sync` types with the `rayon`
+/// feature, and every type without it.
+#[cfg(feature = "rayon")]
+pub trait Element: Send + Sync {}
+
+#[cfg(feature = "rayon")]
+impl<T: Send + Sync> Element for T {}
+
+/// The element types that operators may split across threads: `Send + Sync` types with the `rayon`
+/// feature, and every type without it.
+#[cfg(not(feature = "rayon"))]
+pub trait Element {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T> Element for T {}
+
+/// Vectors made of `Vec1` columns of one element type, for code that works on any dimension.
+pub trait Columns {
+    type Element;
+
+    /// The number of columns.
+    const DIM: usize;
+
+    /// Panics if `axis` is not less than `DIM`.
+    fn column(&self, axis: usize) -> &Vec1<Self::Element>;
+
+    /// Panics if `axis` is not less than `DIM`.
+    fn column_mut(&mut self, axis: usize) -> &mut Vec1<Self::Element>;
+}
+
+#[must_use]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InsertOutcome<T> {
+    Replaced(T),
+    Appended,
+    OutOfBounds,
+}
+
+impl<T> InsertOutcome<T> {
+    pub(crate) fn map<U, F: FnOnce(T) -> U>(self, f: F) -> InsertOutcome<U> {
+        match self {
+            InsertOutcome::Replaced(value) => InsertOutcome::Replaced(f(value)),
+            InsertOutcome::Appended => InsertOutcome::Appended,
+            InsertOutcome::OutOfBounds => InsertOutcome::OutOfBounds,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SwapRemoved<T> {
+    pub value: T,
+    /// The previous index of the element that was moved into the removed slot.
+    pub moved_from: Option<usize>,
+}
+
+impl<T> SwapRemoved<T> {
+    pub(crate) fn map<U, F: FnOnce(T) -> U>(self, f: F) -> SwapRemoved<U> {
+        SwapRemoved {
+            value: f(self.value),
+            moved_from: self.moved_from,
+        }
+    }
+}
+
+/// Assignment from lazy expressions whose shape broadcasts to `$shape`, so that e.g. a `Vec2`
+/// accepts `Vec1` and scalar expressions as well as `Vec2` ones.
+macro_rules! expr_assign {
+    ([$($gen:tt)*] $vec:ty, $shape:ty) => {
+        expr_assign!(@fused
+            /// On x86 CPUs with AVX2 and FMA, `f32` and `f64` columns take a fused multiply-add that rounds
+            /// `self + a * b` once, where other CPUs round the product first. Results can therefore differ
+            /// between machines, by an ulp where the terms share a sign and by more where they nearly cancel.
+            [$($gen)*] $vec, $shape, AddAssign, add_assign, VMul, FusedOp::MulAdd);
+        expr_assign!(@fused
+            /// On x86 CPUs with AVX2 and FMA, `f32` and `f64` columns take a fused multiply-subtract that rounds
+            /// `self - a * b` once, where other CPUs round the product first. Results can therefore differ
+            /// between machines, by an ulp where the terms have opposite signs and by more where they nearly cancel.
+            [$($gen)*] $vec, $shape, SubAssign, sub_assign, VMul, FusedOp::MulSub);
+        expr_assign!(@fused [$($gen)*] $vec, $shape, AddAssign, add_assign, VDiv, FusedOp::DivAdd);
+        expr_assign!(@fused [$($gen)*] $vec, $shape, SubAssign, sub_assign, VDiv, FusedOp::DivSub);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, AddAssign, add_assign, VAdd<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, SubAssign, sub_assign, VAdd<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, AddAssign, add_assign, VSub<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, SubAssign, sub_assign, VSub<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, AddAssign, add_assign, VNeg<A>, [A]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, SubAssign, sub_assign, VNeg<A>, [A]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, MulAssign, mul_assign, VMul<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, DivAssign, div_assign, VMul<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, MulAssign, mul_assign, VDiv<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, DivAssign, div_assign, VDiv<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, MulAssign, mul_assign, VAdd<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, DivAssign, div_assign, VAdd<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, MulAssign, mul_assign, VSub<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, DivAssign, div_assign, VSub<A, B>, [A, B]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, MulAssign, mul_assign, VNeg<A>, [A]);
+        expr_assign!(@lazy [$($gen)*] $vec, $shape, DivAssign, div_assign, VNeg<A>, [A]);
+    };
+    (@fused $(#[$attr:meta])* [$($gen:tt)*] $vec:ty, $shape:ty, $trait:ident, $method:ident, $node:ident, $op:expr) => {
+        $(#[$attr])*
+        impl<T, A, B, $($gen)*> $trait<$node<A, B>> for $vec
+            where
+                T: Copy + $trait<<$node<A, B> as VExpr>::Output> + Element + 'static,
+                A: VExpr,
+                B: VExpr,
+                A::Output: 'static,
+                B::Output: 'static,
+                $node<A, B>: VExpr + Element,
+                $shape: Combine<<$node<A, B> as VExpr>::Shape, Output=$shape>,
+        {
+            fn $method(&mut self, rhs: $node<A, B>) {
+                if !self.fused_assign($op, &rhs.a, &rhs.b) {
+                    self.chunked_zip_to_expr(&rhs, T::$method);
+                }
+            }
+        }
+    };
+    (@lazy [$($gen:tt)*] $vec:ty, $shape:ty, $trait:ident, $method:ident, $node:ty, [$($node_gen:ident),*]) => {
+        impl<T, $($node_gen,)* $($gen)*> $trait<$node> for $vec
+            where
+                T: Copy + $trait<<$node as VExpr>::Output> + Element,
+                $node: VExpr + Element,
+                $shape: Combine<<$node as VExpr>::Shape, Output=$shape>,
+        {
+            fn $method(&mut self, rhs: $node) {
+                self.chunked_zip_to_expr(&rhs, T::$method);
+            }
+        }
+    };
+}
+
+macro_rules! try_assign {
+    ([$($gen:tt)*] $vec:ty) => {
+        impl<T, $($gen)*> $vec {
+            pub fn try_add_assign<R: VExpr>(&mut self, rhs: R) -> Result<(), SimdVecError> where Self: AddAssign<R> {
+                check_size(self.len(), rhs.try_size()?)?;
+                *self += rhs;
+                Ok(())
+            }
+
+            pub fn try_sub_assign<R: VExpr>(&mut self, rhs: R) -> Result<(), SimdVecError> where Self: SubAssign<R> {
+                check_size(self.len(), rhs.try_size()?)?;
+                *self -= rhs;
+                Ok(())
+            }
+
+            pub fn try_mul_assign<R: VExpr>(&mut self, rhs: R) -> Result<(), SimdVecError> where Self: MulAssign<R> {
+                check_size(self.len(), rhs.try_size()?)?;
+                *self *= rhs;
+                Ok(())
+            }
+
+            pub fn try_div_assign<R: VExpr>(&mut self, rhs: R) -> Result<(), SimdVecError> where Self: DivAssign<R> {
+                check_size(self.len(), rhs.try_size()?)?;
+                *self /= rhs;
+                Ok(())
+            }
+        }
+    };
+}
 
+pub use mat2::{Affine2, Mat2, UniformAffine2, UniformMat2};
+pub use polar::VecPolar;
+pub use vec1::{Magnitude, Vec1};
+pub use vec2::{Vec2, ZeroLength};
+pub use vec3::Vec3;
+pub use vecn::VecN;
+
+mod columns;
+mod mat2;
+mod polar;
 mod vec1;
 mod vec2;
+mod vec3;
+mod vecn;
 
 #[cfg(test)]
 mod tests {
@@ -74,6 +262,31 @@ mod tests {
         assert_eq!(expected, v1);
     }
 
+    #[test]
+    fn add_assign_vec3_to_vec3() {
+        let mut v1 = Vec3 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 5.0].into(),
+            z: vec![7.0, 11.0].into(),
+        };
+
+        let v2 = Vec3 {
+            x: vec![13.0, 17.0].into(),
+            y: vec![19.0, 23.0].into(),
+            z: vec![29.0, 31.0].into(),
+        };
+
+        let expected = Vec3 {
+            x: vec![14.0, 19.0].into(),
+            y: vec![22.0, 28.0].into(),
+            z: vec![36.0, 42.0].into(),
+        };
+
+        v1 += &v2;
+
+        assert_eq!(expected, v1);
+    }
+
     #[test]
     fn sub_assign_vec2_to_vec2() {
         let mut v1 = Vec2 {
@@ -172,6 +385,91 @@ mod tests {
         assert_eq!(expected, v1);
     }
 
+    #[test]
+    fn mul_assign_vec1_to_vec3() {
+        let mut v1 = Vec3 {
+            x: vec![1.0f32, 2.0].into(),
+            y: vec![3.0, 5.0].into(),
+            z: vec![7.0, 11.0].into(),
+        };
+
+        let v2 = Vec1::from(vec![2.0, 3.0]);
+
+        let expected = Vec3 {
+            x: vec![2.0, 6.0].into(),
+            y: vec![6.0, 15.0].into(),
+            z: vec![14.0, 33.0].into(),
+        };
+
+        v1 *= &v2;
+
+        assert_eq!(expected, v1);
+    }
+
+    #[test]
+    fn div_assign_value_to_vec3() {
+        let mut v1 = Vec3 {
+            x: vec![2.0, 6.0].into(),
+            y: vec![6.0, 15.0].into(),
+            z: vec![4.0, 8.0].into(),
+        };
+
+        let expected = Vec3 {
+            x: vec![1.0, 3.0].into(),
+            y: vec![3.0, 7.5].into(),
+            z: vec![2.0, 4.0].into(),
+        };
+
+        v1 /= 2.0;
+
+        assert_eq!(expected, v1);
+    }
+
+    #[test]
+    fn assign_from_overwrites_vec1() {
+        let mut position: Vec1<Length> = Vec1 {
+            values: vec![9.0.into()],
+        };
+
+        let speed: Vec1<Speed> = Vec1 {
+            values: vec![2.0.into(), 3.0.into()],
+        };
+
+        let time = Time::in_seconds(2.0);
+
+        position.assign_from(&speed * &time);
+
+        let expected = Vec1 {
+            values: vec![4.0.into(), 6.0.into()],
+        };
+
+        assert_eq!(expected, position);
+    }
+
+    #[test]
+    fn assign_from_overwrites_vec2() {
+        let mut v1 = Vec2 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 5.0].into(),
+        };
+
+        let v2 = Vec2 {
+            x: vec![7.0, 11.0].into(),
+            y: vec![13.0, 17.0].into(),
+        };
+
+        let scale = Vec1::from(vec![2.0, 3.0]);
+
+        let expected = Vec2 {
+            x: vec![14.0, 33.0].into(),
+            y: vec![26.0, 51.0].into(),
+        };
+
+        v1.assign_from(&v2 * &scale);
+
+        assert_eq!(expected, v1);
+    }
+
     #[test]
     fn add_assign_mul_vec1() {
         let mut position: Vec1<Length> = Vec1 {
@@ -0,0 +1,137 @@
+use super::*;
+
+/// Polar coordinates stored as radius and angle columns, with angles in radians.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VecPolar<T> {
+    pub radius: Vec1<T>,
+    pub angle: Vec1<T>,
+}
+
+impl<T> Default for VecPolar<T> {
+    fn default() -> Self {
+        VecPolar {
+            radius: Vec1::default(),
+            angle: Vec1::default(),
+        }
+    }
+}
+
+impl<T> VecPolar<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<(&T, &T)> {
+        let index = index.index();
+
+        self.radius.get(index)
+            .and_then(|r| self.angle.get(index).map(|a| (r, a)))
+    }
+
+    pub fn len(&self) -> usize {
+        assert_len!(self.radius.len(), self.angle.len());
+        self.radius.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Float + Scalar + Default + AddAssign<T> + MulAssign<T> + Element + 'static> VecPolar<T> {
+    /// Converts from cartesian coordinates, with angles in (-pi, pi].
+    pub fn from_cartesian(source: &Vec2<T>) -> Self {
+        let mut radius = Vec1::new();
+        radius.get_magnitude(source);
+
+        let mut angle = source.y.clone();
+        angle.atan2_assign(&source.x);
+        angle.wrap_angles();
+
+        VecPolar { radius, angle }
+    }
+
+    pub fn to_cartesian(&self, target: &mut Vec2<T>) {
+        assert_len!(self.radius.len(), self.angle.len());
+
+        target.x.values.clone_from(&self.angle.values);
+        target.x.cos_assign();
+        target.x *= &self.radius;
+
+        target.y.values.clone_from(&self.angle.values);
+        target.y.sin_assign();
+        target.y *= &self.radius;
+    }
+
+    pub fn scale_by(&mut self, factors: &Vec1<T>) {
+        self.radius *= factors;
+    }
+
+    pub fn scale_by_value(&mut self, factor: T) {
+        self.radius *= factor;
+    }
+
+    pub fn rotate_by(&mut self, angles: &Vec1<T>) {
+        self.angle += angles;
+        self.angle.wrap_angles();
+    }
+
+    pub fn rotate_by_value(&mut self, angle: T) {
+        self.angle.zip_to_value(angle, T::add_assign);
+        self.angle.wrap_angles();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn wrap_angles() {
+        let mut angles = Vec1::from(vec![0.0, PI, -PI, 3.0 * PI, -FRAC_PI_2, 2.0 * PI + 0.5, -2.0 * PI - 0.5]);
+        angles.wrap_angles();
+
+        let expected = [0.0, PI, PI, PI, -FRAC_PI_2, 0.5, -0.5];
+        for (a, e) in angles.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-12, "{} != {}", a, e);
+        }
+    }
+
+    #[test]
+    fn cartesian_round_trip() {
+        let cartesian = Vec2 {
+            x: vec![1.0, 0.0, -2.0, -1.0, 0.0].into(),
+            y: vec![0.0, 3.0, 0.0, -1.0, 0.0].into(),
+        };
+
+        let polar = VecPolar::from_cartesian(&cartesian);
+
+        assert_eq!(vec![1.0, 3.0, 2.0, 2f64.sqrt(), 0.0], polar.radius.values);
+        assert_eq!(vec![0.0, FRAC_PI_2, PI, -3.0 * PI / 4.0, 0.0], polar.angle.values);
+
+        let mut back = Vec2::new();
+        polar.to_cartesian(&mut back);
+
+        for (a, b) in back.x.iter().zip(cartesian.x.iter()).chain(back.y.iter().zip(cartesian.y.iter())) {
+            assert!((a - b).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn polar_arithmetic() {
+        let mut polar = VecPolar {
+            radius: vec![1.0, 2.0].into(),
+            angle: vec![3.0, -1.0].into(),
+        };
+
+        polar.scale_by_value(2.0);
+        polar.scale_by(&vec![1.5, 0.5].into());
+        polar.rotate_by_value(0.5);
+        polar.rotate_by(&vec![0.0, -PI].into());
+
+        assert_eq!(vec![3.0, 2.0], polar.radius.values);
+        assert!((polar.angle.values[0] - (3.5 - 2.0 * PI)).abs() < 1e-15);
+        assert!((polar.angle.values[1] - (PI - 0.5)).abs() < 1e-15);
+    }
+}
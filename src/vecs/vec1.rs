@@ -1,4 +1,5 @@
 use super::*;
+use crate::error::check_len;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Vec1<T> {
@@ -11,6 +12,20 @@ impl<T> Default for Vec1<T> {
     }
 }
 
+impl<T> Columns for Vec1<T> {
+    type Element = T;
+
+    const DIM: usize = 1;
+
+    fn column(&self, axis: usize) -> &Vec1<T> {
+        &std::slice::from_ref(self)[axis]
+    }
+
+    fn column_mut(&mut self, axis: usize) -> &mut Vec1<T> {
+        &mut std::slice::from_mut(self)[axis]
+    }
+}
+
 impl<T> From<Vec<T>> for Vec1<T> {
     fn from(values: Vec<T>) -> Self {
         Self { values }
@@ -22,22 +37,78 @@ impl<T> Vec1<T> {
         Vec1 { values: vec![] }
     }
 
-    pub fn insert(&mut self, value: T, index: usize) {
+    pub fn insert<I: VecIndex>(&mut self, value: T, index: I) -> InsertOutcome<T> {
+        let index = index.index();
+
         if let Some(v) = self.values.get_mut(index) {
-            *v = value;
+            InsertOutcome::Replaced(std::mem::replace(v, value))
+        } else if self.len() == index {
+            self.values.push(value);
+            InsertOutcome::Appended
         } else {
-            if self.len() == index {
-                self.values.push(value);
-            }
+            InsertOutcome::OutOfBounds
         }
     }
 
-    pub fn get(&self, index: usize) -> Option<&T> {
-        self.values.get(index)
+    pub fn insert_or_resize_with<I: VecIndex, F: FnMut() -> T>(&mut self, value: T, index: I, f: F) -> InsertOutcome<T> {
+        let index = index.index();
+
+        if index > self.len() {
+            self.values.resize_with(index, f);
+        }
+
+        self.insert(value, index)
     }
 
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        self.values.get_mut(index)
+    pub fn swap_remove(&mut self, index: usize) -> Option<SwapRemoved<T>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let last = self.len() - 1;
+        let value = self.values.swap_remove(index);
+        let moved_from = if index == last { None } else { Some(last) };
+
+        Some(SwapRemoved { value, moved_from })
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index < self.len() {
+            Some(self.values.remove(index))
+        } else {
+            None
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.values.retain(f);
+    }
+
+    pub fn retain_by_mask(&mut self, mask: &[bool]) {
+        assert_len!(self.len(), mask.len());
+
+        let mut mask = mask.iter();
+        self.values.retain(|_| mask.next().copied().unwrap_or(true));
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> std::vec::Drain<'_, T> {
+        self.values.drain(range)
+    }
+
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<&T> {
+        self.values.get(index.index())
+    }
+
+    pub fn get_mut<I: VecIndex>(&mut self, index: I) -> Option<&mut T> {
+        self.values.get_mut(index.index())
     }
 
     pub fn iter(&self) -> impl Iterator<Item=&T> {
@@ -70,7 +141,7 @@ impl<T> Vec1<T> {
     }
 
     pub fn zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+        assert_len!(self.len(), rhs.len());
 
         self.iter_mut()
             .zip(rhs.iter())
@@ -78,7 +149,7 @@ impl<T> Vec1<T> {
     }
 
     pub fn zip_to_vec2<T2: Copy, F: Fn(&mut T, T2, T2)>(&mut self, rhs: &Vec2<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+        assert_len!(self.len(), rhs.len());
 
         self.iter_mut()
             .zip(rhs.x.iter())
@@ -86,9 +157,19 @@ impl<T> Vec1<T> {
             .for_each(|((v, x), y)| f(v, *x, *y));
     }
 
+    pub fn zip_to_vec3<T2: Copy, F: Fn(&mut T, T2, T2, T2)>(&mut self, rhs: &Vec3<T2>, f: F) {
+        assert_len!(self.len(), rhs.len());
+
+        self.iter_mut()
+            .zip(rhs.x.iter())
+            .zip(rhs.y.iter())
+            .zip(rhs.z.iter())
+            .for_each(|(((v, x), y), z)| f(v, *x, *y, *z));
+    }
+
     pub fn zip_to_vec1_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, a: &Vec1<T2>, b: &Vec1<T3>, f: F) {
-        debug_assert_eq!(self.len(), a.len());
-        debug_assert_eq!(self.len(), b.len());
+        assert_len!(self.len(), a.len());
+        assert_len!(self.len(), b.len());
 
         self.iter_mut()
             .zip(a.iter())
@@ -97,12 +178,118 @@ impl<T> Vec1<T> {
     }
 
     pub fn zip_to_vec1_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, a: &Vec1<T2>, b: T3, f: F) {
-        debug_assert_eq!(self.len(), a.len());
+        assert_len!(self.len(), a.len());
 
         self.iter_mut()
             .zip(a.iter())
             .for_each(|(v, a)| f(v, *a, b));
     }
+
+    pub fn zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) {
+        assert_len!(Some(self.len()), expr.size());
+
+        self.iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| f(v, expr.get(i, 0)));
+    }
+
+    pub fn assign_from<E: VExpr<Output=T, Shape=Dim1>>(&mut self, expr: E) {
+        let len = expr.size().unwrap_or(0);
+
+        self.values.clear();
+        self.values.extend((0..len).map(|i| expr.get(i, 0)));
+    }
+
+    // the operator fallbacks for types without a SIMD kernel, split into chunks like the kernels
+
+    fn chunked_zip_to_value<T2: Copy + Element, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: T2, f: F) where T: Element {
+        simd::chunks1(self.as_mut_slice(), |dst| dst.iter_mut().for_each(|v| f(v, rhs)));
+    }
+
+    fn chunked_zip_to_vec1<T2: Copy + Element, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: &Vec1<T2>, f: F) where T: Element {
+        assert_len!(self.len(), rhs.len());
+
+        simd::chunks2(self.as_mut_slice(), rhs.as_slice(), |dst, rhs| {
+            dst.iter_mut()
+                .zip(rhs.iter())
+                .for_each(|(v, r)| f(v, *r));
+        });
+    }
+
+    pub(crate) fn chunked_zip_to_expr<E: VExpr + Element, F: Fn(&mut T, E::Output) + Sync>(&mut self, expr: &E, f: F) where T: Element {
+        assert_len!(Some(self.len()), expr.size());
+
+        self.chunked_zip_column_to_expr(0, expr, f);
+    }
+
+    pub(crate) fn chunked_zip_column_to_expr<E: VExpr + Element, F: Fn(&mut T, E::Output) + Sync>(&mut self, axis: usize, expr: &E, f: F) where T: Element {
+        simd::chunks1_indexed(self.as_mut_slice(), |start, dst| {
+            dst.iter_mut()
+                .enumerate()
+                .for_each(|(i, v)| f(v, expr.get(start + i, axis)));
+        });
+    }
+
+    pub(crate) fn fused_assign_column<A: VExpr, B: VExpr>(&mut self, op: FusedOp, axis: usize, a: &A, b: &B) -> bool
+        where
+            T: 'static,
+            A::Output: 'static,
+            B::Output: 'static,
+    {
+        match (a.column(axis), b.column(axis), b.value()) {
+            (Some(a), Some(b), _) => simd::fused(op, self, a, b),
+            (Some(a), None, Some(b)) => simd::fused_scalar(op, self, a, &b),
+            _ => false,
+        }
+    }
+
+    fn fused_assign<A: VExpr, B: VExpr>(&mut self, op: FusedOp, a: &A, b: &B) -> bool
+        where
+            T: 'static,
+            A::Output: 'static,
+            B::Output: 'static,
+    {
+        self.fused_assign_column(op, 0, a, b)
+    }
+}
+
+impl<T> Vec1<T> {
+    pub fn try_zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec1(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec2<T2: Copy, F: Fn(&mut T, T2, T2)>(&mut self, rhs: &Vec2<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec2(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec3<T2: Copy, F: Fn(&mut T, T2, T2, T2)>(&mut self, rhs: &Vec3<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec3(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec1_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, a: &Vec1<T2>, b: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), a.len())?;
+        check_len(self.len(), b.len())?;
+        self.zip_to_vec1_and_vec1(a, b, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec1_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, a: &Vec1<T2>, b: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), a.len())?;
+        self.zip_to_vec1_and_value(a, b, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) -> Result<(), SimdVecError> {
+        check_size(self.len(), expr.try_size()?)?;
+        self.zip_to_expr(expr, f);
+        Ok(())
+    }
 }
 
 impl<T: Default> Vec1<T> {
@@ -118,123 +305,477 @@ impl<T: Default> Vec1<T> {
         values
     }
 
+    pub fn insert_or_resize<I: VecIndex>(&mut self, value: T, index: I) -> InsertOutcome<T> {
+        self.insert_or_resize_with(value, index, T::default)
+    }
+
     fn resize_with_default(&mut self, len: usize) {
         self.values.resize_with(len, T::default);
     }
 }
 
-impl<T: Float + Default> Vec1<T> {
-    pub fn get_magnitude_squared(&mut self, source: &Vec2<T>) {
-        self.resize_with_default(source.len());
-        self.zip_to_vec2(source, Self::calc_magnitude_squared);
+const LANES: usize = 4;
+
+impl<T: Copy> Vec1<T> {
+    pub fn sum(&self) -> T where T: Default + AddAssign<T> {
+        let mut lanes = [T::default(); LANES];
+
+        let chunks = self.values.chunks_exact(LANES);
+        let remainder = chunks.remainder();
+
+        chunks.for_each(|chunk| {
+            lanes.iter_mut()
+                .zip(chunk.iter())
+                .for_each(|(lane, v)| *lane += *v);
+        });
+
+        Self::reduce_lanes(lanes, remainder.iter().copied())
     }
 
-    fn calc_magnitude_squared(value: &mut T, x: T, y: T) {
-        *value = (x * x) + (y * y);
+    pub fn sum_kahan(&self) -> T where T: Default + Add<T, Output=T> + Sub<T, Output=T> {
+        sum::kahan(self.as_slice())
     }
 
-    pub fn get_magnitude(&mut self, source: &Vec2<T>) {
-        self.resize_with_default(source.len());
-        self.zip_to_vec2(source, Self::calc_magnitude);
+    pub fn sum_pairwise(&self) -> T where T: Default + Add<T, Output=T> {
+        sum::pairwise(self.as_slice())
     }
 
-    fn calc_magnitude(value: &mut T, x: T, y: T) {
-        *value = ((x * x) + (y * y)).sqrt();
+    /// A sum whose result is bit-identical regardless of SIMD width or thread count.
+    pub fn sum_deterministic(&self) -> T where T: Default + Add<T, Output=T> {
+        sum::deterministic(self.as_slice())
     }
-}
 
-impl<'a, T1: Copy + AddAssign<T2>, T2: Copy> AddAssign<&'a Vec1<T2>> for Vec1<T1> {
-    fn add_assign(&mut self, rhs: &'a Vec1<T2>) {
-        self.zip_to_vec1(rhs, T1::add_assign)
+    /// The sum divided by the length, converted to the scalar type `T / T` (e.g. `f32`, or `f64` for `Length`).
+    pub fn mean<S>(&self) -> Option<T> where T: Default + AddAssign<T> + Div<T, Output=S> + Div<S, Output=T>, S: FromPrimitive {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.sum() / S::from_usize(self.len())?)
+        }
     }
-}
 
-impl<'a, T1: Copy + AddAssign<T1>, T2: Copy + Mul<T3, Output=T1>, T3: Copy> AddAssign<VMul<'a, Vec1<T2>, Vec1<T3>>> for Vec1<T1> {
-    fn add_assign(&mut self, rhs: VMul<'a, Vec1<T2>, Vec1<T3>>) {
-        self.zip_to_vec1_and_vec1(rhs.a, rhs.b, |a, b, c| *a += b * c);
+    pub fn dot<T2: Copy, T3: Copy + Default + AddAssign<T3>>(&self, rhs: &Vec1<T2>) -> T3 where T: Mul<T2, Output=T3> {
+        assert_len!(self.len(), rhs.len());
+
+        Self::dot_slices(self.as_slice(), rhs.as_slice())
     }
-}
 
-impl<'a, T1: Copy + AddAssign<T1>, T2: Copy + Mul<T3, Output=T1>, T3: Copy> AddAssign<VMul<'a, Vec1<T2>, T3>> for Vec1<T1> {
-    fn add_assign(&mut self, rhs: VMul<'a, Vec1<T2>, T3>) {
-        self.zip_to_vec1_and_value(rhs.a, *rhs.b, |a, b, c| *a += b * c);
+    fn dot_slices<T2: Copy, T3: Copy + Default + AddAssign<T3>>(lhs: &[T], rhs: &[T2]) -> T3 where T: Mul<T2, Output=T3> {
+        let mut lanes = [T3::default(); LANES];
+
+        let lhs = lhs.chunks_exact(LANES);
+        let rhs = rhs.chunks_exact(LANES);
+        let remainder = lhs.remainder()
+            .iter()
+            .zip(rhs.remainder().iter())
+            .map(|(a, b)| *a * *b);
+
+        lhs.zip(rhs).for_each(|(a, b)| {
+            lanes.iter_mut()
+                .zip(a.iter().zip(b.iter()))
+                .for_each(|(lane, (a, b))| *lane += *a * *b);
+        });
+
+        Self::reduce_lanes(lanes, remainder)
     }
-}
 
-impl<'a, T1: Copy + AddAssign<T1>, T2: Copy + Div<T3, Output=T1>, T3: Copy> AddAssign<VDiv<'a, Vec1<T2>, Vec1<T3>>> for Vec1<T1> {
-    fn add_assign(&mut self, rhs: VDiv<'a, Vec1<T2>, Vec1<T3>>) {
-        self.zip_to_vec1_and_vec1(rhs.a, rhs.b, |a, b, c| *a += b / c);
+    fn reduce_lanes<U: Copy + AddAssign<U>, I: Iterator<Item=U>>(lanes: [U; LANES], remainder: I) -> U {
+        let [mut a, mut b, c, d] = lanes;
+        a += c;
+        b += d;
+        a += b;
+        remainder.for_each(|v| a += v);
+        a
     }
-}
 
-impl<'a, T1: Copy + AddAssign<T1>, T2: Copy + Div<T3, Output=T1>, T3: Copy> AddAssign<VDiv<'a, Vec1<T2>, T3>> for Vec1<T1> {
-    fn add_assign(&mut self, rhs: VDiv<'a, Vec1<T2>, T3>) {
-        self.zip_to_vec1_and_value(rhs.a, *rhs.b, |a, b, c| *a += b / c);
+    pub fn min(&self) -> Option<T> where T: PartialOrd {
+        self.argmin().map(|i| self.values[i])
     }
-}
 
-impl<'a, T1: Copy + SubAssign<T2>, T2: Copy> SubAssign<&'a Vec1<T2>> for Vec1<T1> {
-    fn sub_assign(&mut self, rhs: &'a Vec1<T2>) {
-        self.zip_to_vec1(rhs, T1::sub_assign)
+    pub fn max(&self) -> Option<T> where T: PartialOrd {
+        self.argmax().map(|i| self.values[i])
     }
-}
 
-impl<'a, T1: Copy + SubAssign<T1>, T2: Copy + Mul<T3, Output=T1>, T3: Copy> SubAssign<VMul<'a, Vec1<T2>, Vec1<T3>>> for Vec1<T1> {
-    fn sub_assign(&mut self, rhs: VMul<'a, Vec1<T2>, Vec1<T3>>) {
-        self.zip_to_vec1_and_vec1(rhs.a, rhs.b, |a, b, c| *a -= b * c);
+    pub fn argmin(&self) -> Option<usize> where T: PartialOrd {
+        self.arg_by(|candidate, best| candidate < best)
     }
-}
 
-impl<'a, T1: Copy + SubAssign<T1>, T2: Copy + Mul<T3, Output=T1>, T3: Copy> SubAssign<VMul<'a, Vec1<T2>, T3>> for Vec1<T1> {
-    fn sub_assign(&mut self, rhs: VMul<'a, Vec1<T2>, T3>) {
-        self.zip_to_vec1_and_value(rhs.a, *rhs.b, |a, b, c| *a -= b * c);
+    pub fn argmax(&self) -> Option<usize> where T: PartialOrd {
+        self.arg_by(|candidate, best| candidate > best)
     }
-}
 
-impl<'a, T1: Copy + SubAssign<T1>, T2: Copy + Div<T3, Output=T1>, T3: Copy> SubAssign<VDiv<'a, Vec1<T2>, Vec1<T3>>> for Vec1<T1> {
-    fn sub_assign(&mut self, rhs: VDiv<'a, Vec1<T2>, Vec1<T3>>) {
-        self.zip_to_vec1_and_vec1(rhs.a, rhs.b, |a, b, c| *a -= b / c);
+    fn arg_by<F: Fn(&T, &T) -> bool>(&self, replaces: F) -> Option<usize> {
+        let mut values = self.iter().enumerate();
+        let (mut best_index, mut best) = values.next()?;
+
+        for (i, v) in values {
+            if replaces(v, best) {
+                best_index = i;
+                best = v;
+            }
+        }
+
+        Some(best_index)
     }
 }
 
-impl<'a, T1: Copy + SubAssign<T1>, T2: Copy + Div<T3, Output=T1>, T3: Copy> SubAssign<VDiv<'a, Vec1<T2>, T3>> for Vec1<T1> {
-    fn sub_assign(&mut self, rhs: VDiv<'a, Vec1<T2>, T3>) {
-        self.zip_to_vec1_and_value(rhs.a, *rhs.b, |a, b, c| *a -= b / c);
+#[cfg(feature = "rayon")]
+impl<T: Send + Sync> Vec1<T> {
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, T> {
+        self.values.par_iter()
     }
-}
 
-impl<T: Copy + MulAssign<T>> MulAssign<&Self> for Vec1<T> {
-    fn mul_assign(&mut self, rhs: &Vec1<T>) {
-        self.zip_to_vec1(rhs, T::mul_assign)
+    pub fn par_iter_mut(&mut self) -> rayon::slice::IterMut<'_, T> {
+        self.values.par_iter_mut()
+    }
+
+    pub fn par_zip_to_value<T2: Copy + Send + Sync, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: T2, f: F) {
+        self.par_iter_mut()
+            .with_min_len(par::threshold())
+            .for_each(|v| f(v, rhs));
+    }
+
+    pub fn par_zip_to_vec1<T2: Copy + Send + Sync, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: &Vec1<T2>, f: F) {
+        assert_len!(self.len(), rhs.len());
+
+        self.par_iter_mut()
+            .zip(rhs.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|(v, r)| f(v, *r));
+    }
+
+    pub fn par_zip_to_vec2<T2: Copy + Send + Sync, F: Fn(&mut T, T2, T2) + Sync>(&mut self, rhs: &Vec2<T2>, f: F) {
+        assert_len!(self.len(), rhs.len());
+
+        self.par_iter_mut()
+            .zip(rhs.x.par_iter())
+            .zip(rhs.y.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|((v, x), y)| f(v, *x, *y));
+    }
+
+    pub fn par_zip_to_vec1_and_vec1<T2: Copy + Send + Sync, T3: Copy + Send + Sync, F: Fn(&mut T, T2, T3) + Sync>(&mut self, a: &Vec1<T2>, b: &Vec1<T3>, f: F) {
+        assert_len!(self.len(), a.len());
+        assert_len!(self.len(), b.len());
+
+        self.par_iter_mut()
+            .zip(a.par_iter())
+            .zip(b.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|((v, a), b)| f(v, *a, *b));
+    }
+
+    pub fn par_zip_to_vec1_and_value<T2: Copy + Send + Sync, T3: Copy + Send + Sync, F: Fn(&mut T, T2, T3) + Sync>(&mut self, a: &Vec1<T2>, b: T3, f: F) {
+        assert_len!(self.len(), a.len());
+
+        self.par_iter_mut()
+            .zip(a.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|(v, a)| f(v, *a, b));
+    }
+
+    /// `dot` over chunks of `par::threshold()` elements, with the partial sums added in order.
+    /// The result depends on the threshold but not on the number of threads, and equals `dot`
+    /// for columns no longer than the threshold.
+    pub fn par_dot<T2: Copy + Send + Sync, T3: Copy + Default + AddAssign<T3> + Send>(&self, rhs: &Vec1<T2>) -> T3 where T: Copy + Mul<T2, Output=T3> {
+        assert_len!(self.len(), rhs.len());
+
+        let len = par::threshold();
+
+        self.values.par_chunks(len)
+            .zip(rhs.values.par_chunks(len))
+            .map(|(a, b)| Self::dot_slices(a, b))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .reduce(|mut sum, v| {
+                sum += v;
+                sum
+            })
+            .unwrap_or_default()
+    }
+
+    /// Equals `min` for columns without NaN.
+    pub fn par_min(&self) -> Option<T> where T: Copy + PartialOrd {
+        self.par_iter()
+            .with_min_len(par::threshold())
+            .copied()
+            .reduce_with(|best, v| if v < best { v } else { best })
+    }
+
+    /// Equals `max` for columns without NaN.
+    pub fn par_max(&self) -> Option<T> where T: Copy + PartialOrd {
+        self.par_iter()
+            .with_min_len(par::threshold())
+            .copied()
+            .reduce_with(|best, v| if v > best { v } else { best })
+    }
+
+    /// Bit-identical to `sum_pairwise`.
+    pub fn par_sum_pairwise(&self) -> T where T: Copy + Default + Add<T, Output=T> {
+        sum::par_pairwise(self.as_slice())
+    }
+
+    /// Bit-identical to `sum_deterministic`.
+    pub fn par_sum_deterministic(&self) -> T where T: Copy + Default + Add<T, Output=T> {
+        sum::par_deterministic(self.as_slice())
     }
 }
 
-impl<T: Copy + MulAssign<T>> MulAssign<T> for Vec1<T> {
-    fn mul_assign(&mut self, rhs: T) {
-        self.zip_to_value(rhs, T::mul_assign)
+impl<T: Default> Vec1<T> {
+    pub fn get_magnitude_squared<E: Norm<Squared=T>, S: Magnitude<E>>(&mut self, source: &S) {
+        source.magnitude_squared_into(self);
     }
+
+    pub fn get_magnitude<S: Magnitude<T>>(&mut self, source: &S) where T: Norm {
+        source.magnitude_into(self);
+    }
+}
+
+macro_rules! float_fn {
+    ($($(#[$attr:meta])* $name:ident, $assign:ident($($arg:ident: $t:ty),*);)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self, $($arg: $t),*) -> Self {
+                let mut result = self.clone();
+                result.$assign($($arg),*);
+                result
+            }
+        )*
+    };
 }
 
-impl<T: Copy + DivAssign<T>> DivAssign<&Self> for Vec1<T> {
-    fn div_assign(&mut self, rhs: &Vec1<T>) {
-        self.zip_to_vec1(rhs, T::div_assign)
+/// Element-wise math. `sqrt`, `abs` and `recip` have SIMD kernels for `f32` and `f64`,
+/// the rest map the `Float` method over each value.
+impl<T: Float + 'static> Vec1<T> {
+    float_fn! {
+        sqrt, sqrt_assign();
+        abs, abs_assign();
+        recip, recip_assign();
+        exp, exp_assign();
+        ln, ln_assign();
+        powi, powi_assign(n: i32);
+        powf, powf_assign(n: T);
+        floor, floor_assign();
+        round, round_assign();
+        signum, signum_assign();
+        mul_add, mul_add_assign(a: &Self, b: &Self);
+        /// For `f32` and `f64` a SIMD polynomial within 2 ulp of `sin_exact`. Values beyond 8192 (`f32`)
+        /// or 1e6 (`f64`) in magnitude, where the range reduction loses precision, fall back to `sin_exact`.
+        sin, sin_assign();
+        /// Within 2 ulp of `cos_exact`, with the same fallback as `sin`.
+        cos, cos_assign();
+        /// Within 4 ulp of `tan_exact`, with the same fallback as `sin`.
+        tan, tan_assign();
+        /// Within 2 ulp of `atan_exact` for all finite values.
+        atan, atan_assign();
+        /// Within 2 ulp of `atan2_exact`. Non-finite values and zero `x` fall back to `atan2_exact`.
+        atan2, atan2_assign(x: &Self);
+        sin_exact, sin_exact_assign();
+        cos_exact, cos_exact_assign();
+        tan_exact, tan_exact_assign();
+        atan_exact, atan_exact_assign();
+        atan2_exact, atan2_exact_assign(x: &Self);
+    }
+
+    pub fn sqrt_assign(&mut self) {
+        self.unary_assign(UnaryOp::Sqrt, T::sqrt);
+    }
+
+    pub fn abs_assign(&mut self) {
+        self.unary_assign(UnaryOp::Abs, T::abs);
+    }
+
+    pub fn recip_assign(&mut self) {
+        self.unary_assign(UnaryOp::Recip, T::recip);
+    }
+
+    pub fn exp_assign(&mut self) {
+        self.map_assign(T::exp);
+    }
+
+    pub fn ln_assign(&mut self) {
+        self.map_assign(T::ln);
+    }
+
+    pub fn powi_assign(&mut self, n: i32) {
+        self.map_assign(|v| v.powi(n));
+    }
+
+    pub fn powf_assign(&mut self, n: T) {
+        self.map_assign(|v| v.powf(n));
+    }
+
+    pub fn floor_assign(&mut self) {
+        self.map_assign(T::floor);
+    }
+
+    pub fn round_assign(&mut self) {
+        self.map_assign(T::round);
+    }
+
+    pub fn signum_assign(&mut self) {
+        self.map_assign(T::signum);
+    }
+
+    /// `self * a + b`. The SIMD path only rounds once when the CPU supports FMA.
+    pub fn mul_add_assign(&mut self, a: &Self, b: &Self) {
+        assert_len!(self.len(), a.len());
+        assert_len!(self.len(), b.len());
+
+        let mut result = b.clone();
+        if !simd::fused(FusedOp::MulAdd, &mut result, self, a) {
+            result.zip_to_vec1_and_vec1(self, a, |r, s, a| *r = s.mul_add(a, *r));
+        }
+        *self = result;
+    }
+
+    pub fn sin_assign(&mut self) {
+        self.unary_assign(UnaryOp::Sin, T::sin);
+    }
+
+    pub fn cos_assign(&mut self) {
+        self.unary_assign(UnaryOp::Cos, T::cos);
+    }
+
+    pub fn tan_assign(&mut self) {
+        self.unary_assign(UnaryOp::Tan, T::tan);
+    }
+
+    pub fn atan_assign(&mut self) {
+        self.unary_assign(UnaryOp::Atan, T::atan);
+    }
+
+    /// Replaces each value `y` with `y.atan2(x)`.
+    pub fn atan2_assign(&mut self, x: &Self) {
+        if !simd::binary(BinaryOp::Atan2, self, x) {
+            self.zip_to_vec1(x, |y, x| *y = y.atan2(x));
+        }
+    }
+
+    /// `(self.sin(), self.cos())`, with the accuracy of each. The two run their own range reduction,
+    /// so this is no faster than calling both.
+    pub fn sin_cos(&self) -> (Self, Self) {
+        (self.sin(), self.cos())
+    }
+
+    /// Wraps angles in radians into (-pi, pi].
+    pub fn wrap_angles(&mut self) {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let tau = pi + pi;
+
+        self.map_assign(|a| a - tau * ((a - pi) / tau).ceil());
+    }
+
+    pub fn sin_exact_assign(&mut self) {
+        self.map_assign(T::sin);
+    }
+
+    pub fn cos_exact_assign(&mut self) {
+        self.map_assign(T::cos);
+    }
+
+    pub fn tan_exact_assign(&mut self) {
+        self.map_assign(T::tan);
     }
+
+    pub fn atan_exact_assign(&mut self) {
+        self.map_assign(T::atan);
+    }
+
+    pub fn atan2_exact_assign(&mut self, x: &Self) {
+        self.zip_to_vec1(x, |y, x| *y = y.atan2(x));
+    }
+
+    pub fn sin_cos_exact(&self) -> (Self, Self) {
+        (self.sin_exact(), self.cos_exact())
+    }
+
+    fn unary_assign(&mut self, op: UnaryOp, f: fn(T) -> T) {
+        if !simd::unary(op, self) {
+            self.map_assign(f);
+        }
+    }
+
+    fn map_assign<F: Fn(T) -> T>(&mut self, f: F) {
+        self.values.iter_mut()
+            .for_each(|v| *v = f(*v));
+    }
+}
+
+/// Vectors whose rows have a length, e.g. `Vec2<Length>` with squared magnitudes in `Area`.
+pub trait Magnitude<T: Norm> {
+    fn magnitude_squared_into(&self, target: &mut Vec1<T::Squared>);
+
+    fn magnitude_into(&self, target: &mut Vec1<T>);
 }
 
-impl<T: Copy + DivAssign<T>> DivAssign<T> for Vec1<T> {
-    fn div_assign(&mut self, rhs: T) {
-        self.zip_to_value(rhs, T::div_assign)
+impl<T: Norm + Default> Magnitude<T> for Vec2<T> where T::Squared: Default {
+    fn magnitude_squared_into(&self, target: &mut Vec1<T::Squared>) {
+        target.resize_with_default(self.len());
+        target.zip_to_vec2(self, |value, x, y| *value = (x * x) + (y * y));
+    }
+
+    fn magnitude_into(&self, target: &mut Vec1<T>) {
+        target.resize_with_default(self.len());
+        target.zip_to_vec2(self, |value, x, y| *value = T::root((x * x) + (y * y)));
     }
 }
 
-impl<'a, T1: Mul<T2>, T2> Mul<&'a Vec1<T2>> for &'a Vec2<T1> {
-    type Output = VMul<'a, Vec2<T1>, Vec1<T2>>;
+impl<T: Norm + Default> Magnitude<T> for Vec3<T> where T::Squared: Default {
+    fn magnitude_squared_into(&self, target: &mut Vec1<T::Squared>) {
+        target.resize_with_default(self.len());
+        target.zip_to_vec3(self, |value, x, y, z| *value = (x * x) + (y * y) + (z * z));
+    }
 
-    fn mul(self, rhs: &'a Vec1<T2>) -> Self::Output {
-        VMul { a: self, b: rhs }
+    fn magnitude_into(&self, target: &mut Vec1<T>) {
+        target.resize_with_default(self.len());
+        target.zip_to_vec3(self, |value, x, y, z| *value = T::root((x * x) + (y * y) + (z * z)));
     }
 }
 
+/// `Vec1` op `&Vec1` and `Vec1` op a single value, using the SIMD kernels when both sides are the same float type.
+/// Adding and subtracting take a value of the element type, e.g. `Vec1<Length> += Length`, while multiplying and
+/// dividing take any `Scalar`, e.g. `Vec1<Length> *= 2.0`.
+macro_rules! vec1_assign {
+    ($trait:ident, $method:ident, $binary:expr, $scalar:expr, $value:ident) => {
+        impl<'a, T1: Copy + $trait<T2> + Element + 'static, T2: Copy + Element + 'static> $trait<&'a Vec1<T2>> for Vec1<T1> {
+            fn $method(&mut self, rhs: &'a Vec1<T2>) {
+                if !simd::binary($binary, self, rhs) {
+                    self.chunked_zip_to_vec1(rhs, T1::$method)
+                }
+            }
+        }
+
+        vec1_assign!(@$value $trait, $method, $scalar);
+    };
+    (@element $trait:ident, $method:ident, $scalar:expr) => {
+        impl<T: Copy + $trait<T> + Element + 'static> $trait<T> for Vec1<T> {
+            fn $method(&mut self, rhs: T) {
+                if !simd::scalar($scalar, self, &rhs) {
+                    self.chunked_zip_to_value(rhs, T::$method)
+                }
+            }
+        }
+    };
+    (@scalar $trait:ident, $method:ident, $scalar:expr) => {
+        impl<T1: Copy + $trait<T2> + Element + 'static, T2: Scalar + Element + 'static> $trait<T2> for Vec1<T1> {
+            fn $method(&mut self, rhs: T2) {
+                if !simd::scalar($scalar, self, &rhs) {
+                    self.chunked_zip_to_value(rhs, T1::$method)
+                }
+            }
+        }
+    };
+}
+
+vec1_assign!(AddAssign, add_assign, BinaryOp::Add, ScalarOp::Add, element);
+vec1_assign!(SubAssign, sub_assign, BinaryOp::Sub, ScalarOp::Sub, element);
+vec1_assign!(MulAssign, mul_assign, BinaryOp::Mul, ScalarOp::Mul, scalar);
+vec1_assign!(DivAssign, div_assign, BinaryOp::Div, ScalarOp::Div, scalar);
+
+try_assign!([] Vec1<T>);
+expr_assign!([] Vec1<T>, Dim1);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,12 +821,197 @@ mod tests {
         v -= VDiv::new(&p, &t);
     }
 
+    #[test]
+    fn magnitude_of_vec2_and_vec3() {
+        let v2 = Vec2 {
+            x: vec![3.0, 0.0].into(),
+            y: vec![4.0, 1.0].into(),
+        };
+
+        let v3 = Vec3 {
+            x: vec![2.0, 0.0].into(),
+            y: vec![3.0, 0.0].into(),
+            z: vec![6.0, 2.0].into(),
+        };
+
+        let mut magnitude = Vec1::new();
+
+        magnitude.get_magnitude(&v2);
+        assert_eq!(vec![5.0, 1.0], magnitude.values);
+
+        magnitude.get_magnitude(&v3);
+        assert_eq!(vec![7.0, 2.0], magnitude.values);
+
+        magnitude.get_magnitude_squared(&v3);
+        assert_eq!(vec![49.0, 4.0], magnitude.values);
+    }
+
+    #[test]
+    fn try_add_assign_length_mismatch() {
+        let mut v1 = Vec1::from(vec![1.0, 2.0]);
+        let v2 = Vec1::from(vec![1.0, 2.0, 3.0]);
+
+        let result = v1.try_add_assign(&v2);
+
+        assert_eq!(Err(SimdVecError::LengthMismatch { expected: 2, found: 3 }), result);
+        assert_eq!(vec![1.0, 2.0], v1.values);
+    }
+
+    #[test]
+    fn try_add_assign_nested_length_mismatch() {
+        let mut v1 = Vec1::from(vec![1.0, 2.0]);
+        let v2 = Vec1::from(vec![1.0, 2.0]);
+        let v3 = Vec1::from(vec![1.0]);
+
+        let result = v1.try_add_assign(&v2 * &v3);
+
+        assert_eq!(Err(SimdVecError::LengthMismatch { expected: 2, found: 1 }), result);
+        assert_eq!(vec![1.0, 2.0], v1.values);
+    }
+
+    #[test]
+    fn try_zip_to_vec1_and_vec1() {
+        let mut v1 = Vec1::from(vec![1.0, 2.0]);
+        let a = Vec1::from(vec![1.0, 2.0]);
+        let b = Vec1::from(vec![3.0]);
+
+        assert!(v1.try_zip_to_vec1_and_vec1(&a, &b, |v, a, b| *v += a * b).is_err());
+        assert_eq!(Ok(()), v1.try_zip_to_vec1_and_vec1(&a, &a, |v, a, b| *v += a * b));
+        assert_eq!(vec![2.0, 6.0], v1.values);
+    }
+
+    #[test]
+    fn sum_and_mean() {
+        let values: Vec1<Length> = Vec1 {
+            values: (1..=9).map(|i| (i as f64).into()).collect(),
+        };
+
+        assert_eq!(Length::in_meters(45.0), values.sum());
+        assert_eq!(Some(Length::in_meters(5.0)), values.mean());
+        assert_eq!(None, Vec1::<f64>::new().mean());
+        assert_eq!(Some(2.5f32), Vec1::from(vec![1.0f32, 2.0, 3.0, 4.0]).mean());
+    }
+
+    #[test]
+    fn float_math() {
+        let values = Vec1::from(vec![4.0, -2.25, 0.5, 9.0, -1.0]);
+
+        assert_eq!(vec![4.0, 2.25, 0.5, 9.0, 1.0], values.abs().values);
+        assert_eq!(vec![2.0, 1.5, 0.5f64.sqrt(), 3.0, 1.0], values.abs().sqrt().values);
+        assert_eq!(vec![0.25, -1.0 / 2.25, 2.0, 1.0 / 9.0, -1.0], values.recip().values);
+        assert_eq!(vec![16.0, 5.0625, 0.25, 81.0, 1.0], values.powi(2).values);
+        assert_eq!(vec![4.0, -3.0, 0.0, 9.0, -1.0], values.floor().values);
+        assert_eq!(vec![4.0, -2.0, 1.0, 9.0, -1.0], values.round().values);
+        assert_eq!(vec![1.0, -1.0, 1.0, 1.0, -1.0], values.signum().values);
+
+        let mut ln = values.abs();
+        ln.exp_assign();
+        ln.ln_assign();
+        assert!(ln.iter().zip(values.abs().iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+    }
+
+    #[test]
+    fn float_math_f32_matches_scalar() {
+        let values = Vec1::from((0..19).map(|i| i as f32 * 0.75 + 0.1).collect::<Vec<_>>());
+
+        let sqrt = values.sqrt();
+        let recip = values.recip();
+
+        for (i, v) in values.iter().enumerate() {
+            assert_eq!(v.sqrt(), sqrt.values[i]);
+            assert_eq!(v.recip(), recip.values[i]);
+        }
+    }
+
+    #[test]
+    fn trig_matches_exact() {
+        let angles = Vec1::from((-50..50).map(|i| i as f64 * 0.137).collect::<Vec<_>>());
+        let x = Vec1::from((-50..50).map(|i| 3.0 - i as f64 * 0.05).collect::<Vec<_>>());
+
+        let (sin, cos) = angles.sin_cos();
+        let (sin_exact, cos_exact) = angles.sin_cos_exact();
+        let close = |a: &Vec1<f64>, b: &Vec1<f64>| a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() <= 1e-15 * b.abs().max(1.0));
+
+        assert!(close(&sin, &sin_exact));
+        assert!(close(&cos, &cos_exact));
+        assert!(close(&angles.atan(), &angles.atan_exact()));
+        assert!(close(&angles.atan2(&x), &angles.atan2_exact(&x)));
+        assert!(angles.tan().iter().zip(angles.tan_exact().iter()).all(|(a, b)| (a - b).abs() <= 1e-14 * b.abs().max(1.0)));
+    }
+
+    #[test]
+    fn mul_add() {
+        let values = Vec1::from(vec![1.0, 2.0, 3.0]);
+        let a = Vec1::from(vec![2.0, 3.0, 4.0]);
+        let b = Vec1::from(vec![0.5, 0.25, 0.125]);
+
+        assert_eq!(vec![2.5, 6.25, 12.125], values.mul_add(&a, &b).values);
+    }
+
+    #[test]
+    fn compensated_sums() {
+        let mut values = vec![1.0];
+        values.extend(std::iter::repeat_n(1e-16, 1000));
+        let values = Vec1::from(values);
+
+        assert_eq!(1.0, values.iter().fold(0.0, |sum, v| sum + v));
+        assert!(values.sum_kahan() > 1.0);
+        assert!(values.sum_pairwise() > 1.0);
+        assert!(values.sum_deterministic() > 1.0);
+    }
+
+    #[test]
+    fn typed_dot() {
+        let speed: Vec1<Speed> = Vec1 {
+            values: vec![1.0.into(), 2.0.into(), 3.0.into(), 4.0.into(), 5.0.into()],
+        };
+
+        let time: Vec1<Time> = Vec1 {
+            values: vec![2.0.into(), 2.0.into(), 2.0.into(), 1.0.into(), 1.0.into()],
+        };
+
+        let distance: Length = speed.dot(&time);
+
+        assert_eq!(Length::in_meters(21.0), distance);
+    }
+
+    #[test]
+    fn min_max() {
+        let values = Vec1::from(vec![3.0, -1.0, 7.0, -1.0, 7.0, 2.0]);
+
+        assert_eq!(Some(-1.0), values.min());
+        assert_eq!(Some(7.0), values.max());
+        assert_eq!(Some(1), values.argmin());
+        assert_eq!(Some(2), values.argmax());
+        assert_eq!(None, Vec1::<f64>::new().argmin());
+    }
+
+    #[test]
+    fn swap_remove_reports_moved_index() {
+        let mut vec = Vec1::from(vec!['a', 'b', 'c']);
+
+        assert_eq!(Some(SwapRemoved { value: 'a', moved_from: Some(2) }), vec.swap_remove(0));
+        assert_eq!(vec!['c', 'b'], vec.values);
+
+        assert_eq!(Some(SwapRemoved { value: 'b', moved_from: None }), vec.swap_remove(1));
+        assert_eq!(None, vec.swap_remove(1));
+    }
+
+    #[test]
+    fn retain_by_mask() {
+        let mut vec = Vec1::from(vec![1, 2, 3, 4]);
+
+        vec.retain_by_mask(&[true, false, false, true]);
+
+        assert_eq!(vec![1, 4], vec.values);
+    }
+
     #[test]
     fn insert_at_end() {
         let mut vec = Vec1::new();
 
-        vec.insert('a', 0);
-        vec.insert('b', 1);
+        assert_eq!(InsertOutcome::Appended, vec.insert('a', 0));
+        assert_eq!(InsertOutcome::Appended, vec.insert('b', 1));
 
         assert_eq!(vec!['a', 'b'], vec.values);
     }
@@ -294,7 +1020,7 @@ mod tests {
     fn insert_beyond_end() {
         let mut vec = Vec1::new();
 
-        vec.insert('b', 1);
+        assert_eq!(InsertOutcome::OutOfBounds, vec.insert('b', 1));
 
         assert_eq!(Vec::<char>::new(), vec.values);
     }
@@ -303,12 +1029,76 @@ mod tests {
     fn insert_in_middle() {
         let mut vec = Vec1::new();
 
-        vec.insert('a', 0);
-        vec.insert('b', 1);
-        vec.insert('c', 2);
+        let _ = vec.insert('a', 0);
+        let _ = vec.insert('b', 1);
+        let _ = vec.insert('c', 2);
 
-        vec.insert('d', 1);
+        assert_eq!(InsertOutcome::Replaced('b'), vec.insert('d', 1));
 
         assert_eq!(vec!['a', 'd', 'c'], vec.values);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn insert_or_resize_beyond_end() {
+        let mut vec = Vec1::new();
+
+        assert_eq!(InsertOutcome::Appended, vec.insert_or_resize(3, 2));
+        assert_eq!(InsertOutcome::Appended, vec.insert_or_resize_with(5, 4, || 1));
+
+        assert_eq!(vec![0, 0, 3, 1, 5], vec.values);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_matches_serial() {
+        let len = 3 * crate::par::threshold() + 5;
+        let a: Vec1<f64> = (0..len).map(|i| (i % 97) as f64 * 0.25).collect::<Vec<_>>().into();
+        let b: Vec1<f64> = (0..len).map(|i| (i % 13) as f64 + 1.0).collect::<Vec<_>>().into();
+
+        let mut serial = a.clone();
+        serial.zip_to_vec1_and_vec1(&a, &b, |v, a, b| *v += a / b);
+        let mut parallel = a.clone();
+        parallel.par_zip_to_vec1_and_vec1(&a, &b, |v, a, b| *v += a / b);
+        assert_eq!(serial, parallel);
+
+        // operators split SIMD kernels across threads above the threshold
+        let mut fused = a.clone();
+        fused += &a * &b;
+        let mut expected = a.clone();
+        expected.zip_to_vec1_and_vec1(&a, &b, |v, a, b| *v += a * b);
+        assert_eq!(expected, fused);
+
+        assert_eq!(a.sum_deterministic().to_bits(), a.par_sum_deterministic().to_bits());
+        assert_eq!(a.sum_pairwise().to_bits(), a.par_sum_pairwise().to_bits());
+        assert_eq!(a.iter().copied().fold(0.0, f64::max), a.par_iter().copied().reduce(|| 0.0, f64::max));
+
+        // integer-valued products sum exactly in any order
+        assert_eq!(a.dot(&b), a.par_dot(&b));
+        assert_eq!(a.min(), a.par_min());
+        assert_eq!(a.max(), a.par_max());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_unit_operators_match_serial() {
+        let len = 3 * crate::par::threshold() + 5;
+        let p: Vec1<Length> = (0..len).map(|i| Length::in_meters(i as f64 * 0.5)).collect::<Vec<_>>().into();
+        let v: Vec1<Speed> = (0..len).map(|i| Speed::in_meters_per_second((i % 7) as f64)).collect::<Vec<_>>().into();
+        let dt = Time::in_seconds(0.25);
+        let scale: Vec1<f64> = (0..len).map(|i| (i % 5) as f64).collect::<Vec<_>>().into();
+
+        let mut parallel = p.clone();
+        parallel += &v * &dt;
+        parallel -= &p;
+        parallel += &p;
+
+        let mut serial = p.clone();
+        serial.zip_to_vec1(&v, |p, v| *p += v * dt);
+        serial.zip_to_vec1(&p, |a, b| *a -= b);
+        serial.zip_to_vec1(&p, |a, b| *a += b);
+
+        assert_eq!(serial, parallel);
+        assert_eq!(p.dot(&scale), p.par_dot(&scale));
+        assert_eq!(v.max(), v.par_max());
+    }
+}
@@ -1,5 +1,8 @@
 use super::*;
+use crate::error::check_len;
 
+/// Two named columns. Converts to and from `VecN<T, 2>` and shares its implementations, while
+/// the methods here keep the tuple-based API.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Vec2<T> {
     pub x: Vec1<T>,
@@ -8,19 +11,17 @@ pub struct Vec2<T> {
 
 impl<T> Default for Vec2<T> {
     fn default() -> Self {
-        Vec2 {
-            x: Vec1::default(),
-            y: Vec1::default(),
-        }
+        VecN::default().into()
     }
 }
 
 impl<T: Default> Vec2<T> {
+    pub fn insert_or_resize<I: VecIndex>(&mut self, x_value: T, y_value: T, index: I) -> InsertOutcome<(T, T)> {
+        self.insert_or_resize_with(x_value, y_value, index, || (T::default(), T::default()))
+    }
+
     pub fn default_with_len(len: usize) -> Self {
-        Vec2 {
-            x: Vec1::default_with_len(len),
-            y: Vec1::default_with_len(len),
-        }
+        VecN::default_with_len(len).into()
     }
 }
 
@@ -29,239 +30,391 @@ impl<T> Vec2<T> {
         Self::default()
     }
 
-    pub fn get(&self, index: usize) -> Option<(&T, &T)> {
-        self.x.get(index)
-            .and_then(|x| self.y.get(index).map(|y| (x, y)))
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<(&T, &T)> {
+        columns::get(self.columns(), index.index()).map(|[x, y]| (x, y))
     }
 
-    pub fn get_mut(&mut self, index: usize) -> Option<(&mut T, &mut T)> {
-        let x = &mut self.x;
-        let y = &mut self.y;
+    pub fn get_mut<I: VecIndex>(&mut self, index: I) -> Option<(&mut T, &mut T)> {
+        columns::get_mut(self.columns_mut(), index.index()).map(|[x, y]| (x, y))
+    }
 
-        x.get_mut(index)
-            .and_then(move |x| y.get_mut(index).map(|y| (x, y)))
+    pub fn insert<I: VecIndex>(&mut self, x_value: T, y_value: T, index: I) -> InsertOutcome<(T, T)> {
+        columns::insert(self.columns_mut(), [x_value, y_value], index.index()).map(|[x, y]| (x, y))
     }
 
-    pub fn insert(&mut self, x_value: T, y_value: T, index: usize) {
-        if let Some((x, y)) = self.get_mut(index) {
-            *x = x_value;
-            *y = y_value;
-        } else {
-            if self.len() == index {
-                self.x.values.push(x_value);
-                self.y.values.push(y_value);
-            }
-        }
+    pub fn insert_or_resize_with<I: VecIndex, F: FnMut() -> (T, T)>(&mut self, x_value: T, y_value: T, index: I, mut f: F) -> InsertOutcome<(T, T)> {
+        columns::insert_or_resize_with(self.columns_mut(), [x_value, y_value], index.index(), || {
+            let (x, y) = f();
+            [x, y]
+        }).map(|[x, y]| (x, y))
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Option<SwapRemoved<(T, T)>> {
+        columns::swap_remove(self.columns_mut(), index).map(|removed| removed.map(|[x, y]| (x, y)))
     }
 
-    pub fn len(&self) -> usize {
-        self.x.len()
+    pub fn remove(&mut self, index: usize) -> Option<(T, T)> {
+        columns::remove(self.columns_mut(), index).map(|[x, y]| (x, y))
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub fn retain<F: FnMut(&T, &T) -> bool>(&mut self, mut f: F) {
+        columns::retain(self.columns_mut(), |[x, y]| f(x, y));
     }
 
-    pub fn zip_to_value<T2: Copy, F: Fn(&mut T, T2) + 'static>(&mut self, rhs: T2, f: F) {
-        self.x.iter_mut().for_each(|x| f(x, rhs));
-        self.y.iter_mut().for_each(|x| f(x, rhs));
+    pub fn drain<R: RangeBounds<usize> + Clone>(&mut self, range: R) -> impl Iterator<Item=(T, T)> + '_ {
+        columns::drain(self.columns_mut(), range).map(|[x, y]| (x, y))
     }
 
     pub fn zip_both_to_value<T2: Copy, F: Fn(&mut T, &mut T, T2) + 'static>(&mut self, rhs: T2, f: F) {
-        self.x.iter_mut()
-            .zip(self.y.iter_mut())
-            .for_each(|(x, y)| f(x, y, rhs));
+        columns::zip_rows_to_value(self.columns_mut(), rhs, |[x, y], r| f(x, y, r));
     }
 
-    pub fn zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+    pub fn zip_both_to_vec1<T2: Copy, F: Fn(&mut T, &mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
+        columns::zip_rows_to_vec1(self.columns_mut(), rhs, |[x, y], r| f(x, y, r));
+    }
 
-        self.x.iter_mut()
-            .zip(rhs.iter())
-            .for_each(|(l, r)| f(l, *r));
+    pub fn zip_to_vec2<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec2<T2>, f: F) {
+        columns::zip_to_columns(self.columns_mut(), rhs.columns(), f);
+    }
 
-        self.y.iter_mut()
-            .zip(rhs.iter())
-            .for_each(|(l, r)| f(l, *r));
+    pub fn zip_both_to_vec2<T2: Copy, F: Fn(&mut T, &mut T, T2, T2)>(&mut self, rhs: &Vec2<T2>, f: F) {
+        columns::zip_rows_to_columns(self.columns_mut(), rhs.columns(), |[x1, y1], [x2, y2]| f(x1, y1, x2, y2));
     }
 
-    pub fn zip_both_to_vec1<T2: Copy, F: Fn(&mut T, &mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+    pub fn zip_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) {
+        columns::zip_to_columns_and_value(self.columns_mut(), vec2.columns(), value, f);
+    }
 
-        self.x.iter_mut()
-            .zip(self.y.iter_mut())
-            .zip(rhs.iter())
-            .for_each(|((x, y), r)| f(x, y, *r));
+    pub fn zip_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_to_columns_and_vec1(self.columns_mut(), vec2.columns(), vec1, f);
     }
 
-    pub fn zip_to_vec2<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec2<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+    pub fn zip_both_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) {
+        columns::zip_rows_to_columns_and_value(self.columns_mut(), vec2.columns(), value, |[x1, y1], [x2, y2], v| f(x1, y1, x2, y2, v));
+    }
 
-        self.x.iter_mut()
-            .zip(rhs.x.iter())
-            .for_each(|(l, r)| f(l, *r));
+    pub fn zip_both_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_rows_to_columns_and_vec1(self.columns_mut(), vec2.columns(), vec1, |[x1, y1], [x2, y2], v| f(x1, y1, x2, y2, v));
+    }
 
-        self.y.iter_mut()
-            .zip(rhs.y.iter())
-            .for_each(|(l, r)| f(l, *r));
+    pub fn assign_from<E: VExpr<Output=T, Shape=Dim2>>(&mut self, expr: E) {
+        self.assign_columns(&expr);
     }
+}
 
-    pub fn zip_both_to_vec2<T2: Copy, F: Fn(&mut T, &mut T, T2, T2)>(&mut self, rhs: &Vec2<T2>, f: F) {
-        debug_assert_eq!(self.len(), rhs.len());
+impl<T> Vec2<T> {
+    pub fn try_zip_both_to_vec1<T2: Copy, F: Fn(&mut T, &mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_both_to_vec1(rhs, f);
+        Ok(())
+    }
 
-        self.x.iter_mut()
-            .zip(self.y.iter_mut())
-            .zip(rhs.x.iter())
-            .zip(rhs.y.iter())
-            .for_each(|(((x1, y1), x2), y2)| f(x1, y1, *x2, *y2));
+    pub fn try_zip_to_vec2<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec2<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec2(rhs, f);
+        Ok(())
     }
 
-    pub fn zip_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) {
-        debug_assert_eq!(self.len(), vec2.len());
+    pub fn try_zip_both_to_vec2<T2: Copy, F: Fn(&mut T, &mut T, T2, T2)>(&mut self, rhs: &Vec2<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_both_to_vec2(rhs, f);
+        Ok(())
+    }
 
-        self.x.iter_mut()
-            .zip(vec2.x.iter())
-            .for_each(|(x1, x2)| f(x1, *x2, value));
+    pub fn try_zip_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec2.len())?;
+        self.zip_to_vec2_and_value(vec2, value, f);
+        Ok(())
+    }
 
-        self.y.iter_mut()
-            .zip(vec2.y.iter())
-            .for_each(|(y1, y2)| f(y1, *y2, value));
+    pub fn try_zip_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec2.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_to_vec2_and_vec1(vec2, vec1, f);
+        Ok(())
     }
 
-    pub fn zip_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) {
-        debug_assert_eq!(self.len(), vec2.len());
-        debug_assert_eq!(self.len(), vec1.len());
+    pub fn try_zip_both_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec2.len())?;
+        self.zip_both_to_vec2_and_value(vec2, value, f);
+        Ok(())
+    }
 
-        self.x.iter_mut()
-            .zip(vec2.x.iter())
-            .zip(vec1.iter())
-            .for_each(|((x1, x2), v)| f(x1, *x2, *v));
+    pub fn try_zip_both_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec2.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_both_to_vec2_and_vec1(vec2, vec1, f);
+        Ok(())
+    }
+}
 
-        self.y.iter_mut()
-            .zip(vec2.y.iter())
-            .zip(vec1.iter())
-            .for_each(|((y1, y2), v)| f(y1, *y2, *v));
+impl<T: Copy> Vec2<T> {
+    pub fn sum(&self) -> (T, T) where T: Default + AddAssign<T> {
+        (self.x.sum(), self.y.sum())
     }
 
-    pub fn zip_both_to_vec2_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, value: T3, f: F) {
-        debug_assert_eq!(self.len(), vec2.len());
+    pub fn centroid<S>(&self) -> Option<(T, T)> where T: Default + AddAssign<T> + Div<T, Output=S> + Div<S, Output=T>, S: FromPrimitive {
+        Some((self.x.mean()?, self.y.mean()?))
+    }
 
-        self.x.iter_mut()
-            .zip(self.y.iter_mut())
-            .zip(vec2.x.iter())
-            .zip(vec2.y.iter())
-            .for_each(|(((x1, y1), x2), y2)| f(x1, y1, *x2, *y2, value));
+    pub fn centroid_weighted<W, P>(&self, weights: &Vec1<W>) -> Option<(T, T)>
+        where
+            W: Copy + Default + AddAssign<W> + PartialEq,
+            T: Mul<W, Output=P>,
+            P: Copy + Default + AddAssign<P> + Div<W, Output=T>,
+    {
+        let total = weights.sum();
+        if total == W::default() {
+            return None;
+        }
+
+        let x: P = self.x.dot(weights);
+        let y: P = self.y.dot(weights);
+
+        Some((x / total, y / total))
     }
 
-    pub fn zip_both_to_vec2_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, T2, T2, T3)>(&mut self, vec2: &Vec2<T2>, vec1: &Vec1<T3>, f: F) {
-        debug_assert_eq!(self.len(), vec2.len());
-        debug_assert_eq!(self.len(), vec1.len());
+    /// The z component of the 3D cross product, `x1 * y2 - y1 * x2`.
+    pub fn cross<T2: Copy, T3: Sub<T3, Output=T3>>(&self, rhs: &Vec2<T2>) -> Vec1<T3> where T: Mul<T2, Output=T3> {
+        assert_len!(self.len(), rhs.len());
 
-        self.x.iter_mut()
-            .zip(self.y.iter_mut())
-            .zip(vec2.x.iter())
-            .zip(vec2.y.iter())
-            .zip(vec1.iter())
-            .for_each(|((((x1, y1), x2), y2), v)| f(x1, y1, *x2, *y2, *v));
+        Vec1 {
+            values: self.x.iter()
+                .zip(self.y.iter())
+                .zip(rhs.x.iter())
+                .zip(rhs.y.iter())
+                .map(|(((x1, y1), x2), y2)| *x1 * *y2 - *y1 * *x2)
+                .collect(),
+        }
     }
-}
 
-impl<'a, T1, T2, T3> AddAssign<VMul<'a, Vec2<T2>, Vec1<T3>>> for Vec2<T1>
-    where
-        T1: Copy + AddAssign<T1>,
-        T2: Mul<T3, Output=T1> + Copy,
-        T3: Copy,
-{
-    fn add_assign(&mut self, rhs: VMul<'a, Vec2<T2>, Vec1<T3>>) {
-        self.zip_to_vec2_and_vec1(rhs.a, rhs.b, |a, b, c| *a += b.mul(c));
+    /// The vectors rotated a quarter turn counter-clockwise, `(-y, x)`.
+    pub fn perp(&self) -> Self where T: Neg<Output=T> {
+        Vec2 {
+            x: Vec1 { values: self.y.iter().map(|y| -*y).collect() },
+            y: self.x.clone(),
+        }
     }
-}
 
-impl<'a, T1, T2, T3> AddAssign<VDiv<'a, Vec2<T2>, Vec1<T3>>> for Vec2<T1>
-    where
-        T1: Copy + AddAssign<T1>,
-        T2: Div<T3, Output=T1> + Copy,
-        T3: Copy,
-{
-    fn add_assign(&mut self, rhs: VDiv<'a, Vec2<T2>, Vec1<T3>>) {
-        self.zip_to_vec2_and_vec1(rhs.a, rhs.b, |a, b, c| *a += b.div(c));
+    pub fn dot<T2: Copy, T3: Add<T3, Output=T3>>(&self, rhs: &Vec2<T2>) -> Vec1<T3> where T: Mul<T2, Output=T3> {
+        assert_len!(self.len(), rhs.len());
+
+        Vec1 {
+            values: self.x.iter()
+                .zip(self.y.iter())
+                .zip(rhs.x.iter())
+                .zip(rhs.y.iter())
+                .map(|(((x1, y1), x2), y2)| *x1 * *x2 + *y1 * *y2)
+                .collect(),
+        }
     }
 }
 
-impl<'a, T1, T2, T3> SubAssign<VMul<'a, Vec2<T2>, Vec1<T3>>> for Vec2<T1>
-    where
-        T1: Copy + SubAssign<T1>,
-        T2: Mul<T3, Output=T1> + Copy,
-        T3: Copy,
-{
-    fn sub_assign(&mut self, rhs: VMul<'a, Vec2<T2>, Vec1<T3>>) {
-        self.zip_to_vec2_and_vec1(rhs.a, rhs.b, |a, b, c| *a -= b.mul(c));
-    }
+try_assign!([] Vec2<T>);
+expr_assign!([] Vec2<T>, Dim2);
+
+/// What `Vec2::normalize` does with zero-length vectors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ZeroLength<T> {
+    /// Leave the zero vector as it is.
+    Zero,
+    /// Replace the zero vector with the given `(x, y)`.
+    Fallback(T, T),
+    /// Divide by zero anyway, giving NaN.
+    Nan,
 }
 
-impl<'a, T1, T2, T3> SubAssign<VDiv<'a, Vec2<T2>, Vec1<T3>>> for Vec2<T1>
-    where
-        T1: Copy + SubAssign<T1>,
-        T2: Div<T3, Output=T1> + Copy,
-        T3: Copy,
-{
-    fn sub_assign(&mut self, rhs: VDiv<'a, Vec2<T2>, Vec1<T3>>) {
-        self.zip_to_vec2_and_vec1(rhs.a, rhs.b, |a, b, c| *a -= b.div(c));
+impl<T: Norm> Vec2<T> {
+    /// The distance between each pair of points, in the units of `T`.
+    pub fn distance(&self, rhs: &Self) -> Vec1<T> where T: Sub<Output=T> {
+        assert_len!(self.len(), rhs.len());
+
+        Vec1 {
+            values: self.x.iter()
+                .zip(self.y.iter())
+                .zip(rhs.x.iter())
+                .zip(rhs.y.iter())
+                .map(|(((x1, y1), x2), y2)| {
+                    let (dx, dy) = (*x1 - *x2, *y1 - *y2);
+                    T::root((dx * dx) + (dy * dy))
+                })
+                .collect(),
+        }
     }
-}
 
-impl<T: Copy + AddAssign<T>> AddAssign<&Self> for Vec2<T> {
-    fn add_assign(&mut self, rhs: &Vec2<T>) {
-        self.zip_to_vec2(rhs, T::add_assign);
+    /// The unit vectors in the direction of each vector, which are dimensionless for
+    /// unit types (e.g. `Length / Length = f64`).
+    pub fn normalized<R: Float>(&self, zero: ZeroLength<R>) -> Vec2<R> where T: Default + PartialOrd + Div<T, Output=R> {
+        let (x, y) = self.x.iter()
+            .zip(self.y.iter())
+            .map(|(&x, &y)| {
+                let magnitude = T::root((x * x) + (y * y));
+                if magnitude > T::default() {
+                    return (x / magnitude, y / magnitude);
+                }
+
+                match zero {
+                    ZeroLength::Zero => (R::zero(), R::zero()),
+                    ZeroLength::Fallback(x, y) => (x, y),
+                    ZeroLength::Nan => (x / magnitude, y / magnitude),
+                }
+            })
+            .unzip::<R, R, Vec<R>, Vec<R>>();
+
+        Vec2 {
+            x: x.into(),
+            y: y.into(),
+        }
     }
 }
 
-impl<'a, T: Copy + AddAssign<T> + Mul<T, Output=T>> AddAssign<VMul<'a, Vec2<T>, T>> for Vec2<T> {
-    fn add_assign(&mut self, rhs: VMul<'a, Vec2<T>, T>) {
-        self.zip_to_vec2_and_value(rhs.a, *rhs.b, |a, b, c| *a += b.mul(c));
+impl<T: Float> Vec2<T> {
+    pub fn normalize(&mut self, zero: ZeroLength<T>) {
+        self.map_assign(|x, y| {
+            let magnitude = (x * x + y * y).sqrt();
+            if magnitude > T::zero() {
+                return (x / magnitude, y / magnitude);
+            }
+
+            match zero {
+                ZeroLength::Zero => (x, y),
+                ZeroLength::Fallback(x, y) => (x, y),
+                ZeroLength::Nan => (x / magnitude, y / magnitude),
+            }
+        });
     }
-}
 
-impl<T: Copy + SubAssign<T>> SubAssign<&Self> for Vec2<T> {
-    fn sub_assign(&mut self, rhs: &Vec2<T>) {
-        self.zip_to_vec2(rhs, T::sub_assign);
+    /// Rotates each vector counter-clockwise by the matching angle in radians.
+    pub fn rotate_by(&mut self, angles: &Vec1<T>) {
+        assert_len!(self.len(), angles.len());
+
+        self.x.iter_mut()
+            .zip(self.y.iter_mut())
+            .zip(angles.iter())
+            .for_each(|((x, y), angle)| {
+                let (sin, cos) = angle.sin_cos();
+                let (x0, y0) = (*x, *y);
+                *x = x0 * cos - y0 * sin;
+                *y = x0 * sin + y0 * cos;
+            });
+    }
+
+    /// Rotates every vector counter-clockwise by `angle` in radians.
+    pub fn rotate_by_value(&mut self, angle: T) {
+        let (sin, cos) = angle.sin_cos();
+        self.map_assign(|x, y| (x * cos - y * sin, x * sin + y * cos));
+    }
+
+    /// Reflects each vector off the plane with the matching unit `normal`.
+    pub fn reflect(&mut self, normals: &Vec2<T>) {
+        self.zip_both_to_vec2(normals, |x, y, nx, ny| {
+            let d = (*x * nx + *y * ny) * (T::one() + T::one());
+            *x = *x - d * nx;
+            *y = *y - d * ny;
+        });
+    }
+
+    /// Replaces each vector with its projection onto the matching vector in `onto`.
+    /// Projecting onto a zero vector gives a zero vector.
+    pub fn project_onto(&mut self, onto: &Vec2<T>) {
+        self.zip_both_to_vec2(onto, |x, y, ox, oy| {
+            let length_squared = ox * ox + oy * oy;
+            let scale = if length_squared > T::zero() {
+                (*x * ox + *y * oy) / length_squared
+            } else {
+                T::zero()
+            };
+            *x = ox * scale;
+            *y = oy * scale;
+        });
+    }
+
+    /// Scales down any vector longer than `max` to a length of `max`.
+    pub fn clamp_magnitude(&mut self, max: T) {
+        let max_squared = max * max;
+        self.map_assign(|x, y| {
+            let magnitude_squared = x * x + y * y;
+            if magnitude_squared > max_squared {
+                let scale = max / magnitude_squared.sqrt();
+                (x * scale, y * scale)
+            } else {
+                (x, y)
+            }
+        });
     }
-}
 
-impl<T: Copy + MulAssign<T>> MulAssign<&Vec1<T>> for Vec2<T> {
-    fn mul_assign(&mut self, rhs: &Vec1<T>) {
-        self.zip_to_vec1(rhs, T::mul_assign);
+    fn map_assign<F: Fn(T, T) -> (T, T)>(&mut self, f: F) {
+        self.x.iter_mut()
+            .zip(self.y.iter_mut())
+            .for_each(|(x, y)| {
+                let (x1, y1) = f(*x, *y);
+                *x = x1;
+                *y = y1;
+            });
     }
 }
 
-impl<T: Copy + MulAssign<T> + 'static> MulAssign<T> for Vec2<T> {
-    fn mul_assign(&mut self, rhs: T) {
-        self.zip_to_value(rhs, T::mul_assign);
+#[cfg(feature = "rayon")]
+impl<T: Send + Sync> Vec2<T> {
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item=(&T, &T)> {
+        self.x.par_iter().zip(self.y.par_iter())
     }
-}
 
-impl<T: Copy + DivAssign<T>> DivAssign<&Vec1<T>> for Vec2<T> {
-    fn div_assign(&mut self, rhs: &Vec1<T>) {
-        self.zip_to_vec1(rhs, T::div_assign);
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item=(&mut T, &mut T)> {
+        self.x.par_iter_mut().zip(self.y.par_iter_mut())
+    }
+
+    pub fn par_zip_to_value<T2: Copy + Send + Sync, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: T2, f: F) {
+        self.x.par_zip_to_value(rhs, &f);
+        self.y.par_zip_to_value(rhs, &f);
     }
-}
 
-impl<T: Copy + DivAssign<T> + 'static> DivAssign<T> for Vec2<T> {
-    fn div_assign(&mut self, rhs: T) {
-        self.zip_to_value(rhs, T::div_assign);
+    pub fn par_zip_both_to_value<T2: Copy + Send + Sync, F: Fn(&mut T, &mut T, T2) + Sync>(&mut self, rhs: T2, f: F) {
+        self.par_iter_mut()
+            .with_min_len(par::threshold())
+            .for_each(|(x, y)| f(x, y, rhs));
+    }
+
+    pub fn par_zip_to_vec1<T2: Copy + Send + Sync, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: &Vec1<T2>, f: F) {
+        self.x.par_zip_to_vec1(rhs, &f);
+        self.y.par_zip_to_vec1(rhs, &f);
+    }
+
+    pub fn par_zip_both_to_vec1<T2: Copy + Send + Sync, F: Fn(&mut T, &mut T, T2) + Sync>(&mut self, rhs: &Vec1<T2>, f: F) {
+        assert_len!(self.len(), rhs.len());
+
+        self.par_iter_mut()
+            .zip(rhs.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|((x, y), r)| f(x, y, *r));
+    }
+
+    pub fn par_zip_to_vec2<T2: Copy + Send + Sync, F: Fn(&mut T, T2) + Sync>(&mut self, rhs: &Vec2<T2>, f: F) {
+        self.x.par_zip_to_vec1(&rhs.x, &f);
+        self.y.par_zip_to_vec1(&rhs.y, &f);
+    }
+
+    pub fn par_zip_both_to_vec2<T2: Copy + Send + Sync, F: Fn(&mut T, &mut T, T2, T2) + Sync>(&mut self, rhs: &Vec2<T2>, f: F) {
+        assert_len!(self.len(), rhs.len());
+
+        self.par_iter_mut()
+            .zip(rhs.par_iter())
+            .with_min_len(par::threshold())
+            .for_each(|((x1, y1), (x2, y2))| f(x1, y1, *x2, *y2));
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use physics::{Area, Length};
 
     #[test]
     fn insert_at_end() {
         let mut vec = Vec2::new();
 
-        vec.insert('a', 'b', 0);
-        vec.insert('c', 'd', 1);
+        assert_eq!(InsertOutcome::Appended, vec.insert('a', 'b', 0));
+        assert_eq!(InsertOutcome::Appended, vec.insert('c', 'd', 1));
 
         assert_eq!(vec!['a', 'c'], vec.x.values);
         assert_eq!(vec!['b', 'd'], vec.y.values);
@@ -271,23 +424,226 @@ mod tests {
     fn insert_beyond_end() {
         let mut vec = Vec2::new();
 
-        vec.insert('c', 'd', 1);
+        assert_eq!(InsertOutcome::OutOfBounds, vec.insert('c', 'd', 1));
 
         assert!(vec.x.values.is_empty());
         assert!(vec.y.values.is_empty());
     }
 
+    #[test]
+    fn try_mul_assign_length_mismatch() {
+        let mut vec = Vec2 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 4.0].into(),
+        };
+
+        let scale = Vec1::from(vec![2.0]);
+
+        assert_eq!(Err(SimdVecError::LengthMismatch { expected: 2, found: 1 }), vec.try_mul_assign(&scale));
+        assert_eq!(vec![1.0, 2.0], vec.x.values);
+    }
+
     #[test]
     fn insert_into_middle() {
         let mut vec = Vec2::new();
 
-        vec.insert('a', 'b', 0);
-        vec.insert('c', 'd', 1);
-        vec.insert('e', 'f', 2);
+        let _ = vec.insert('a', 'b', 0);
+        let _ = vec.insert('c', 'd', 1);
+        let _ = vec.insert('e', 'f', 2);
 
-        vec.insert('g', 'h', 1);
+        assert_eq!(InsertOutcome::Replaced(('c', 'd')), vec.insert('g', 'h', 1));
 
         assert_eq!(vec!['a', 'g', 'e'], vec.x.values);
         assert_eq!(vec!['b', 'h', 'f'], vec.y.values);
     }
+
+    #[test]
+    fn swap_remove_keeps_columns_in_lockstep() {
+        let mut vec = Vec2 {
+            x: vec!['a', 'c', 'e'].into(),
+            y: vec!['b', 'd', 'f'].into(),
+        };
+
+        let removed = vec.swap_remove(0);
+
+        assert_eq!(Some(SwapRemoved { value: ('a', 'b'), moved_from: Some(2) }), removed);
+        assert_eq!(vec!['e', 'c'], vec.x.values);
+        assert_eq!(vec!['f', 'd'], vec.y.values);
+    }
+
+    #[test]
+    fn retain_and_drain() {
+        let mut vec = Vec2 {
+            x: vec![1, 2, 3, 4, 5].into(),
+            y: vec![5, 4, 3, 2, 1].into(),
+        };
+
+        vec.retain(|x, y| x != y);
+
+        assert_eq!(vec![1, 2, 4, 5], vec.x.values);
+        assert_eq!(vec![5, 4, 2, 1], vec.y.values);
+
+        let drained = vec.drain(1..3).collect::<Vec<_>>();
+
+        assert_eq!(vec![(2, 4), (4, 2)], drained);
+        assert_eq!(vec![1, 5], vec.x.values);
+        assert_eq!(vec![5, 1], vec.y.values);
+    }
+
+    #[test]
+    fn sum_and_centroid() {
+        let vec = Vec2 {
+            x: vec![1.0, 2.0, 3.0, 4.0, 5.0].into(),
+            y: vec![0.0, 0.0, 5.0, 0.0, 0.0].into(),
+        };
+
+        assert_eq!((15.0, 5.0), vec.sum());
+        assert_eq!(Some((3.0, 1.0)), vec.centroid());
+        assert_eq!(None, Vec2::<f64>::new().centroid());
+
+        let vec = Vec2 { x: vec![1.0f32, 2.0].into(), y: vec![-1.0f32, 0.0].into() };
+        assert_eq!(Some((1.5, -0.5)), vec.centroid());
+    }
+
+    #[test]
+    fn centroid_weighted() {
+        let position = Vec2 {
+            x: vec![0.0, 4.0].into(),
+            y: vec![2.0, 6.0].into(),
+        };
+
+        let mass = Vec1::from(vec![3.0, 1.0]);
+
+        assert_eq!(Some((1.0, 3.0)), position.centroid_weighted(&mass));
+        assert_eq!(None, position.centroid_weighted(&Vec1::from(vec![0.0, 0.0])));
+    }
+
+    fn vec2(x: Vec<f64>, y: Vec<f64>) -> Vec2<f64> {
+        Vec2 { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn cross_and_perp() {
+        let a = vec2(vec![1.0, 2.0], vec![0.0, 3.0]);
+        let b = vec2(vec![0.0, 4.0], vec![1.0, 5.0]);
+
+        assert_eq!(vec![1.0, -2.0], a.cross(&b).values);
+        assert_eq!(vec2(vec![-0.0, -3.0], vec![1.0, 2.0]), a.perp());
+        assert_eq!(vec![0.0, 0.0], a.dot(&a.perp()).values);
+    }
+
+    #[test]
+    fn normalize_zero_length_policy() {
+        let a = vec2(vec![3.0, 0.0], vec![4.0, 0.0]);
+
+        let mut zero = a.clone();
+        zero.normalize(ZeroLength::Zero);
+        assert_eq!(vec2(vec![0.6, 0.0], vec![0.8, 0.0]), zero);
+
+        let mut fallback = a.clone();
+        fallback.normalize(ZeroLength::Fallback(1.0, 0.0));
+        assert_eq!(vec2(vec![0.6, 1.0], vec![0.8, 0.0]), fallback);
+
+        let mut nan = a;
+        nan.normalize(ZeroLength::Nan);
+        assert!(nan.x.values[1].is_nan() && nan.y.values[1].is_nan());
+    }
+
+    #[test]
+    fn rotate() {
+        let mut a = vec2(vec![1.0, 0.0], vec![0.0, 2.0]);
+        let mut b = a.clone();
+
+        a.rotate_by(&vec![std::f64::consts::FRAC_PI_2; 2].into());
+        b.rotate_by_value(std::f64::consts::FRAC_PI_2);
+
+        assert_eq!(a, b);
+        assert!((a.x.values[0] - 0.0).abs() < 1e-15 && (a.y.values[0] - 1.0).abs() < 1e-15);
+        assert!((a.x.values[1] + 2.0).abs() < 1e-15 && (a.y.values[1] - 0.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn reflect_project_and_clamp() {
+        let mut a = vec2(vec![1.0, 3.0], vec![-1.0, 4.0]);
+        a.reflect(&vec2(vec![0.0, 1.0], vec![1.0, 0.0]));
+        assert_eq!(vec2(vec![1.0, -3.0], vec![1.0, 4.0]), a);
+
+        let mut b = vec2(vec![2.0, 5.0], vec![3.0, 5.0]);
+        b.project_onto(&vec2(vec![4.0, 0.0], vec![0.0, 0.0]));
+        assert_eq!(vec2(vec![2.0, 0.0], vec![0.0, 0.0]), b);
+
+        let mut c = vec2(vec![3.0, 0.5], vec![4.0, 0.5]);
+        c.clamp_magnitude(2.5);
+        assert_eq!(vec2(vec![1.5, 0.5], vec![2.0, 0.5]), c);
+    }
+
+    #[test]
+    fn dot() {
+        let a = Vec2 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 4.0].into(),
+        };
+
+        let b = Vec2 {
+            x: vec![5.0, 6.0].into(),
+            y: vec![7.0, 8.0].into(),
+        };
+
+        assert_eq!(vec![26.0, 44.0], a.dot(&b).values);
+    }
+
+    fn lengths(x: Vec<f64>, y: Vec<f64>) -> Vec2<Length> {
+        Vec2 {
+            x: Vec1::from(x.into_iter().map(Length::in_meters).collect::<Vec<_>>()),
+            y: Vec1::from(y.into_iter().map(Length::in_meters).collect::<Vec<_>>()),
+        }
+    }
+
+    #[test]
+    fn magnitude_with_units() {
+        let a = lengths(vec![3.0, 0.0, 0.0], vec![4.0, 2.0, 0.0]);
+        let b = lengths(vec![0.0, 0.0, 0.0], vec![0.0, -1.0, 0.0]);
+
+        let mut squared = Vec1::new();
+        squared.get_magnitude_squared(&a);
+        assert_eq!(vec![Area::in_square_meters(25.0), Area::in_square_meters(4.0), Area::default()], squared.values);
+
+        let mut magnitude = Vec1::new();
+        magnitude.get_magnitude(&a);
+        assert_eq!(vec![Length::in_meters(5.0), Length::in_meters(2.0), Length::default()], magnitude.values);
+
+        assert_eq!(vec![Length::in_meters(5.0), Length::in_meters(3.0), Length::default()], a.distance(&b).values);
+
+        let unit: Vec2<f64> = a.normalized(ZeroLength::Fallback(1.0, 0.0));
+        assert_eq!(Vec2 { x: vec![0.6, 0.0, 1.0].into(), y: vec![0.8, 1.0, 0.0].into() }, unit);
+    }
+
+    #[test]
+    fn insert_or_resize_keeps_columns_in_lockstep() {
+        let mut vec = Vec2::new();
+
+        assert_eq!(InsertOutcome::Appended, vec.insert_or_resize(3, 4, 2));
+        assert_eq!(InsertOutcome::Appended, vec.insert_or_resize_with(5, 6, 4, || (1, 2)));
+
+        assert_eq!(vec![0, 0, 3, 1, 5], vec.x.values);
+        assert_eq!(vec![0, 0, 4, 2, 6], vec.y.values);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_zips() {
+        let len = 2 * crate::par::threshold() + 3;
+        let mut v = vec2((0..len).map(|i| i as f64).collect(), vec![1.0; len]);
+        let w = v.clone();
+
+        v.par_zip_both_to_vec2(&w, |x1, y1, x2, y2| {
+            *x1 += y2;
+            *y1 *= x2;
+        });
+        v.par_zip_to_value(2.0, |a, b| *a *= b);
+
+        assert_eq!(Some((&2.0, &0.0)), v.get(0));
+        assert_eq!(Some((&(2.0 * len as f64), &(2.0 * (len - 1) as f64))), v.get(len - 1));
+        assert_eq!(len, v.par_iter().filter(|(x, _)| **x > 0.0).count());
+    }
 }
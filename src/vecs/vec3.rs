@@ -0,0 +1,326 @@
+use super::*;
+use crate::error::check_len;
+
+/// Three named columns. Converts to and from `VecN<T, 3>` and shares its implementations, while
+/// the methods here keep the tuple-based API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vec3<T> {
+    pub x: Vec1<T>,
+    pub y: Vec1<T>,
+    pub z: Vec1<T>,
+}
+
+impl<T> Default for Vec3<T> {
+    fn default() -> Self {
+        VecN::default().into()
+    }
+}
+
+impl<T: Default> Vec3<T> {
+    pub fn insert_or_resize<I: VecIndex>(&mut self, x_value: T, y_value: T, z_value: T, index: I) -> InsertOutcome<(T, T, T)> {
+        self.insert_or_resize_with(x_value, y_value, z_value, index, || (T::default(), T::default(), T::default()))
+    }
+
+    pub fn default_with_len(len: usize) -> Self {
+        VecN::default_with_len(len).into()
+    }
+}
+
+impl<T> Vec3<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<(&T, &T, &T)> {
+        columns::get(self.columns(), index.index()).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn get_mut<I: VecIndex>(&mut self, index: I) -> Option<(&mut T, &mut T, &mut T)> {
+        columns::get_mut(self.columns_mut(), index.index()).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn insert<I: VecIndex>(&mut self, x_value: T, y_value: T, z_value: T, index: I) -> InsertOutcome<(T, T, T)> {
+        columns::insert(self.columns_mut(), [x_value, y_value, z_value], index.index()).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn insert_or_resize_with<I: VecIndex, F: FnMut() -> (T, T, T)>(&mut self, x_value: T, y_value: T, z_value: T, index: I, mut f: F) -> InsertOutcome<(T, T, T)> {
+        columns::insert_or_resize_with(self.columns_mut(), [x_value, y_value, z_value], index.index(), || {
+            let (x, y, z) = f();
+            [x, y, z]
+        }).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Option<SwapRemoved<(T, T, T)>> {
+        columns::swap_remove(self.columns_mut(), index).map(|removed| removed.map(|[x, y, z]| (x, y, z)))
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<(T, T, T)> {
+        columns::remove(self.columns_mut(), index).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn retain<F: FnMut(&T, &T, &T) -> bool>(&mut self, mut f: F) {
+        columns::retain(self.columns_mut(), |[x, y, z]| f(x, y, z));
+    }
+
+    pub fn drain<R: RangeBounds<usize> + Clone>(&mut self, range: R) -> impl Iterator<Item=(T, T, T)> + '_ {
+        columns::drain(self.columns_mut(), range).map(|[x, y, z]| (x, y, z))
+    }
+
+    pub fn zip_all_to_value<T2: Copy, F: Fn(&mut T, &mut T, &mut T, T2) + 'static>(&mut self, rhs: T2, f: F) {
+        columns::zip_rows_to_value(self.columns_mut(), rhs, |[x, y, z], r| f(x, y, z, r));
+    }
+
+    pub fn zip_all_to_vec1<T2: Copy, F: Fn(&mut T, &mut T, &mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
+        columns::zip_rows_to_vec1(self.columns_mut(), rhs, |[x, y, z], r| f(x, y, z, r));
+    }
+
+    pub fn zip_to_vec3<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec3<T2>, f: F) {
+        columns::zip_to_columns(self.columns_mut(), rhs.columns(), f);
+    }
+
+    pub fn zip_all_to_vec3<T2: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2)>(&mut self, rhs: &Vec3<T2>, f: F) {
+        columns::zip_rows_to_columns(self.columns_mut(), rhs.columns(), |[x1, y1, z1], [x2, y2, z2]| f(x1, y1, z1, x2, y2, z2));
+    }
+
+    pub fn zip_to_vec3_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec3: &Vec3<T2>, value: T3, f: F) {
+        columns::zip_to_columns_and_value(self.columns_mut(), vec3.columns(), value, f);
+    }
+
+    pub fn zip_to_vec3_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec3: &Vec3<T2>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_to_columns_and_vec1(self.columns_mut(), vec3.columns(), vec1, f);
+    }
+
+    pub fn zip_all_to_vec3_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2, T3)>(&mut self, vec3: &Vec3<T2>, value: T3, f: F) {
+        columns::zip_rows_to_columns_and_value(self.columns_mut(), vec3.columns(), value, |[x1, y1, z1], [x2, y2, z2], v| f(x1, y1, z1, x2, y2, z2, v));
+    }
+
+    pub fn zip_all_to_vec3_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2, T3)>(&mut self, vec3: &Vec3<T2>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_rows_to_columns_and_vec1(self.columns_mut(), vec3.columns(), vec1, |[x1, y1, z1], [x2, y2, z2], v| f(x1, y1, z1, x2, y2, z2, v));
+    }
+
+    pub fn assign_from<E: VExpr<Output=T, Shape=Dim3>>(&mut self, expr: E) {
+        self.assign_columns(&expr);
+    }
+}
+
+impl<T> Vec3<T> {
+    pub fn try_zip_all_to_vec1<T2: Copy, F: Fn(&mut T, &mut T, &mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_all_to_vec1(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec3<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec3<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec3(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_all_to_vec3<T2: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2)>(&mut self, rhs: &Vec3<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_all_to_vec3(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec3_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec3: &Vec3<T2>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec3.len())?;
+        self.zip_to_vec3_and_value(vec3, value, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vec3_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vec3: &Vec3<T2>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec3.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_to_vec3_and_vec1(vec3, vec1, f);
+        Ok(())
+    }
+
+    pub fn try_zip_all_to_vec3_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2, T3)>(&mut self, vec3: &Vec3<T2>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec3.len())?;
+        self.zip_all_to_vec3_and_value(vec3, value, f);
+        Ok(())
+    }
+
+    pub fn try_zip_all_to_vec3_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, &mut T, &mut T, T2, T2, T2, T3)>(&mut self, vec3: &Vec3<T2>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vec3.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_all_to_vec3_and_vec1(vec3, vec1, f);
+        Ok(())
+    }
+}
+
+impl<T: Copy> Vec3<T> {
+    pub fn sum(&self) -> (T, T, T) where T: Default + AddAssign<T> {
+        (self.x.sum(), self.y.sum(), self.z.sum())
+    }
+
+    pub fn centroid<S>(&self) -> Option<(T, T, T)> where T: Default + AddAssign<T> + Div<T, Output=S> + Div<S, Output=T>, S: FromPrimitive {
+        Some((self.x.mean()?, self.y.mean()?, self.z.mean()?))
+    }
+
+    pub fn centroid_weighted<W, P>(&self, weights: &Vec1<W>) -> Option<(T, T, T)>
+        where
+            W: Copy + Default + AddAssign<W> + PartialEq,
+            T: Mul<W, Output=P>,
+            P: Copy + Default + AddAssign<P> + Div<W, Output=T>,
+    {
+        let total = weights.sum();
+        if total == W::default() {
+            return None;
+        }
+
+        let x: P = self.x.dot(weights);
+        let y: P = self.y.dot(weights);
+        let z: P = self.z.dot(weights);
+
+        Some((x / total, y / total, z / total))
+    }
+
+    pub fn dot<T2: Copy, T3: Add<T3, Output=T3>>(&self, rhs: &Vec3<T2>) -> Vec1<T3> where T: Mul<T2, Output=T3> {
+        assert_len!(self.len(), rhs.len());
+
+        Vec1 {
+            values: self.x.iter()
+                .zip(self.y.iter())
+                .zip(self.z.iter())
+                .zip(rhs.x.iter())
+                .zip(rhs.y.iter())
+                .zip(rhs.z.iter())
+                .map(|(((((x1, y1), z1), x2), y2), z2)| *x1 * *x2 + *y1 * *y2 + *z1 * *z2)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Norm + Sub<Output=T>> Vec3<T> {
+    /// The distance between each pair of points, in the units of `T`.
+    pub fn distance(&self, rhs: &Self) -> Vec1<T> {
+        assert_len!(self.len(), rhs.len());
+
+        Vec1 {
+            values: self.x.iter()
+                .zip(self.y.iter())
+                .zip(self.z.iter())
+                .zip(rhs.x.iter())
+                .zip(rhs.y.iter())
+                .zip(rhs.z.iter())
+                .map(|(((((x1, y1), z1), x2), y2), z2)| {
+                    let (dx, dy, dz) = (*x1 - *x2, *y1 - *y2, *z1 - *z2);
+                    T::root((dx * dx) + (dy * dy) + (dz * dz))
+                })
+                .collect(),
+        }
+    }
+}
+
+try_assign!([] Vec3<T>);
+expr_assign!([] Vec3<T>, Dim3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let a = Vec3 {
+            x: vec![1.0, 0.0].into(),
+            y: vec![2.0, 0.0].into(),
+            z: vec![3.0, 0.0].into(),
+        };
+
+        let b = Vec3 {
+            x: vec![3.0, 0.0].into(),
+            y: vec![5.0, 0.0].into(),
+            z: vec![9.0, 0.0].into(),
+        };
+
+        assert_eq!(vec![7.0, 0.0], a.distance(&b).values);
+    }
+
+    #[test]
+    fn insert_at_end() {
+        let mut vec = Vec3::new();
+
+        assert_eq!(InsertOutcome::Appended, vec.insert('a', 'b', 'c', 0));
+        assert_eq!(InsertOutcome::Appended, vec.insert('d', 'e', 'f', 1));
+
+        assert_eq!(vec!['a', 'd'], vec.x.values);
+        assert_eq!(vec!['b', 'e'], vec.y.values);
+        assert_eq!(vec!['c', 'f'], vec.z.values);
+    }
+
+    #[test]
+    fn insert_beyond_end() {
+        let mut vec = Vec3::new();
+
+        assert_eq!(InsertOutcome::OutOfBounds, vec.insert('d', 'e', 'f', 1));
+
+        assert!(vec.x.values.is_empty());
+        assert!(vec.y.values.is_empty());
+        assert!(vec.z.values.is_empty());
+    }
+
+    #[test]
+    fn insert_into_middle() {
+        let mut vec = Vec3::new();
+
+        let _ = vec.insert('a', 'b', 'c', 0);
+        let _ = vec.insert('d', 'e', 'f', 1);
+        let _ = vec.insert('g', 'h', 'i', 2);
+
+        assert_eq!(InsertOutcome::Replaced(('d', 'e', 'f')), vec.insert('j', 'k', 'l', 1));
+
+        assert_eq!(vec!['a', 'j', 'g'], vec.x.values);
+        assert_eq!(vec!['b', 'k', 'h'], vec.y.values);
+        assert_eq!(vec!['c', 'l', 'i'], vec.z.values);
+    }
+
+    #[test]
+    fn insert_or_resize_keeps_columns_in_lockstep() {
+        let mut vec = Vec3::new();
+
+        assert_eq!(InsertOutcome::Appended, vec.insert_or_resize_with(1, 2, 3, 1, || (7, 8, 9)));
+
+        assert_eq!(vec![7, 1], vec.x.values);
+        assert_eq!(vec![8, 2], vec.y.values);
+        assert_eq!(vec![9, 3], vec.z.values);
+    }
+
+    #[test]
+    fn remove_retain_and_drain() {
+        let mut vec = Vec3 {
+            x: vec![1, 2, 3, 4].into(),
+            y: vec![5, 6, 7, 8].into(),
+            z: vec![9, 10, 11, 12].into(),
+        };
+
+        assert_eq!(Some((1, 5, 9)), vec.remove(0));
+
+        vec.retain(|_, y, _| *y != 7);
+        assert_eq!(vec![2, 4], vec.x.values);
+
+        assert_eq!(vec![(2, 6, 10)], vec.drain(..1).collect::<Vec<_>>());
+        assert_eq!(vec![4], vec.x.values);
+        assert_eq!(vec![8], vec.y.values);
+        assert_eq!(vec![12], vec.z.values);
+
+        vec.clear();
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn get_and_get_mut() {
+        let mut vec = Vec3::new();
+
+        let _ = vec.insert(1, 2, 3, 0);
+
+        if let Some((x, y, z)) = vec.get_mut(0) {
+            *x += 10;
+            *y += 20;
+            *z += 30;
+        }
+
+        assert_eq!(Some((&11, &22, &33)), vec.get(0));
+        assert_eq!(None, vec.get(1));
+    }
+}
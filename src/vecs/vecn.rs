@@ -0,0 +1,535 @@
+use super::*;
+use crate::error::check_len;
+
+/// `N` columns of equal length, one per axis.
+///
+/// The methods here are shared with `Vec2` and `Vec3`, which forward to the same implementations in
+/// `columns`. Column-wise methods apply `f` to each column in turn; the `rows` methods pass every
+/// column's value at an index together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VecN<T, const N: usize> {
+    pub columns: [Vec1<T>; N],
+}
+
+impl<T, const N: usize> Default for VecN<T, N> {
+    fn default() -> Self {
+        VecN {
+            columns: std::array::from_fn(|_| Vec1::default()),
+        }
+    }
+}
+
+impl<T: Default, const N: usize> VecN<T, N> {
+    pub fn insert_or_resize<I: VecIndex>(&mut self, values: [T; N], index: I) -> InsertOutcome<[T; N]> {
+        self.insert_or_resize_with(values, index, || std::array::from_fn(|_| T::default()))
+    }
+
+    pub fn default_with_len(len: usize) -> Self {
+        VecN {
+            columns: std::array::from_fn(|_| Vec1::default_with_len(len)),
+        }
+    }
+}
+
+impl<T, const N: usize> VecN<T, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<I: VecIndex>(&self, index: I) -> Option<[&T; N]> {
+        columns::get(self.columns.each_ref(), index.index())
+    }
+
+    pub fn get_mut<I: VecIndex>(&mut self, index: I) -> Option<[&mut T; N]> {
+        columns::get_mut(self.columns.each_mut(), index.index())
+    }
+
+    pub fn insert<I: VecIndex>(&mut self, values: [T; N], index: I) -> InsertOutcome<[T; N]> {
+        columns::insert(self.columns.each_mut(), values, index.index())
+    }
+
+    pub fn insert_or_resize_with<I: VecIndex, F: FnMut() -> [T; N]>(&mut self, values: [T; N], index: I, f: F) -> InsertOutcome<[T; N]> {
+        columns::insert_or_resize_with(self.columns.each_mut(), values, index.index(), f)
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> Option<SwapRemoved<[T; N]>> {
+        columns::swap_remove(self.columns.each_mut(), index)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<[T; N]> {
+        columns::remove(self.columns.each_mut(), index)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.columns.iter_mut().for_each(|column| column.truncate(len));
+    }
+
+    pub fn clear(&mut self) {
+        self.columns.iter_mut().for_each(|column| column.clear());
+    }
+
+    pub fn retain<F: FnMut([&T; N]) -> bool>(&mut self, f: F) {
+        columns::retain(self.columns.each_mut(), f);
+    }
+
+    pub fn retain_by_mask(&mut self, mask: &[bool]) {
+        columns::retain_by_mask(self.columns.each_mut(), mask);
+    }
+
+    pub fn drain<R: RangeBounds<usize> + Clone>(&mut self, range: R) -> impl Iterator<Item=[T; N]> + '_ {
+        columns::drain(self.columns.each_mut(), range)
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns.first().map_or(0, |column| column.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn zip_to_value<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: T2, f: F) {
+        columns::zip_to_value(self.columns.each_mut(), rhs, f);
+    }
+
+    pub fn zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
+        columns::zip_to_vec1(self.columns.each_mut(), rhs, f);
+    }
+
+    pub fn zip_to_vecn<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &VecN<T2, N>, f: F) {
+        columns::zip_to_columns(self.columns.each_mut(), rhs.columns.each_ref(), f);
+    }
+
+    pub fn zip_to_vecn_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vecn: &VecN<T2, N>, value: T3, f: F) {
+        columns::zip_to_columns_and_value(self.columns.each_mut(), vecn.columns.each_ref(), value, f);
+    }
+
+    pub fn zip_to_vecn_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vecn: &VecN<T2, N>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_to_columns_and_vec1(self.columns.each_mut(), vecn.columns.each_ref(), vec1, f);
+    }
+
+    pub fn zip_rows_to_value<T2: Copy, F: Fn([&mut T; N], T2)>(&mut self, rhs: T2, f: F) {
+        columns::zip_rows_to_value(self.columns.each_mut(), rhs, f);
+    }
+
+    pub fn zip_rows_to_vec1<T2: Copy, F: Fn([&mut T; N], T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
+        columns::zip_rows_to_vec1(self.columns.each_mut(), rhs, f);
+    }
+
+    pub fn zip_rows_to_vecn<T2: Copy, F: Fn([&mut T; N], [T2; N])>(&mut self, rhs: &VecN<T2, N>, f: F) {
+        columns::zip_rows_to_columns(self.columns.each_mut(), rhs.columns.each_ref(), f);
+    }
+
+    pub fn zip_rows_to_vecn_and_value<T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3)>(&mut self, vecn: &VecN<T2, N>, value: T3, f: F) {
+        columns::zip_rows_to_columns_and_value(self.columns.each_mut(), vecn.columns.each_ref(), value, f);
+    }
+
+    pub fn zip_rows_to_vecn_and_vec1<T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3)>(&mut self, vecn: &VecN<T2, N>, vec1: &Vec1<T3>, f: F) {
+        columns::zip_rows_to_columns_and_vec1(self.columns.each_mut(), vecn.columns.each_ref(), vec1, f);
+    }
+
+    pub fn zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) {
+        columns::zip_to_expr(self.columns.each_mut(), expr, f);
+    }
+
+    pub(crate) fn chunked_zip_to_expr<E: VExpr + Element, F: Fn(&mut T, E::Output) + Sync>(&mut self, expr: &E, f: F) where T: Element {
+        columns::chunked_zip_to_expr(self.columns.each_mut(), expr, f);
+    }
+
+    pub fn assign_from<E: VExpr<Output=T, Shape=DimN<N>>>(&mut self, expr: E) {
+        columns::assign_columns(self.columns.each_mut(), &expr);
+    }
+
+    pub(crate) fn fused_assign<A: VExpr, B: VExpr>(&mut self, op: FusedOp, a: &A, b: &B) -> bool
+        where
+            T: 'static,
+            A::Output: 'static,
+            B::Output: 'static,
+    {
+        columns::fused_assign(self.columns.each_mut(), op, a, b)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=[&T; N]> + '_ {
+        columns::rows(self.columns.each_ref())
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item=[&mut T; N]> + '_ {
+        columns::rows_mut(self.columns.each_mut())
+    }
+}
+
+impl<T, const N: usize> VecN<T, N> {
+    pub fn try_zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vec1(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vecn<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &VecN<T2, N>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_to_vecn(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vecn_and_value<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vecn: &VecN<T2, N>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vecn.len())?;
+        self.zip_to_vecn_and_value(vecn, value, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_vecn_and_vec1<T2: Copy, T3: Copy, F: Fn(&mut T, T2, T3)>(&mut self, vecn: &VecN<T2, N>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vecn.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_to_vecn_and_vec1(vecn, vec1, f);
+        Ok(())
+    }
+
+    pub fn try_zip_rows_to_vec1<T2: Copy, F: Fn([&mut T; N], T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_rows_to_vec1(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_rows_to_vecn<T2: Copy, F: Fn([&mut T; N], [T2; N])>(&mut self, rhs: &VecN<T2, N>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), rhs.len())?;
+        self.zip_rows_to_vecn(rhs, f);
+        Ok(())
+    }
+
+    pub fn try_zip_rows_to_vecn_and_value<T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3)>(&mut self, vecn: &VecN<T2, N>, value: T3, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vecn.len())?;
+        self.zip_rows_to_vecn_and_value(vecn, value, f);
+        Ok(())
+    }
+
+    pub fn try_zip_rows_to_vecn_and_vec1<T2: Copy, T3: Copy, F: Fn([&mut T; N], [T2; N], T3)>(&mut self, vecn: &VecN<T2, N>, vec1: &Vec1<T3>, f: F) -> Result<(), SimdVecError> {
+        check_len(self.len(), vecn.len())?;
+        check_len(self.len(), vec1.len())?;
+        self.zip_rows_to_vecn_and_vec1(vecn, vec1, f);
+        Ok(())
+    }
+
+    pub fn try_zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) -> Result<(), SimdVecError> {
+        check_size(self.len(), expr.try_size()?)?;
+        self.zip_to_expr(expr, f);
+        Ok(())
+    }
+}
+
+try_assign!([const N: usize] VecN<T, N>);
+expr_assign!([const N: usize] VecN<T, N>, DimN<N>);
+
+impl<T, const N: usize> Columns for VecN<T, N> {
+    type Element = T;
+
+    const DIM: usize = N;
+
+    fn column(&self, axis: usize) -> &Vec1<T> {
+        &self.columns[axis]
+    }
+
+    fn column_mut(&mut self, axis: usize) -> &mut Vec1<T> {
+        &mut self.columns[axis]
+    }
+}
+
+/// Applies an op column by column, broadcasting `&Vec1` and single-value operands to every column.
+/// The single values follow the `Vec1` impls: the element type for adding and subtracting, any
+/// `Scalar` for multiplying and dividing.
+macro_rules! vecn_assign {
+    ($trait:ident, $method:ident, $value:ident) => {
+        impl<'a, T1: Copy + $trait<T2> + Element + 'static, T2: Copy + Element + 'static, const N: usize> $trait<&'a VecN<T2, N>> for VecN<T1, N> {
+            fn $method(&mut self, rhs: &'a VecN<T2, N>) {
+                self.columns.iter_mut()
+                    .zip(rhs.columns.iter())
+                    .for_each(|(column, rhs)| column.$method(rhs));
+            }
+        }
+
+        impl<'a, T1: Copy + $trait<T2> + Element + 'static, T2: Copy + Element + 'static, const N: usize> $trait<&'a Vec1<T2>> for VecN<T1, N> {
+            fn $method(&mut self, rhs: &'a Vec1<T2>) {
+                self.columns.iter_mut().for_each(|column| column.$method(rhs));
+            }
+        }
+
+        vecn_assign!(@$value $trait, $method);
+    };
+    (@element $trait:ident, $method:ident) => {
+        impl<T: Copy + $trait<T> + Element + 'static, const N: usize> $trait<T> for VecN<T, N> {
+            fn $method(&mut self, rhs: T) {
+                self.columns.iter_mut().for_each(|column| column.$method(rhs));
+            }
+        }
+    };
+    (@scalar $trait:ident, $method:ident) => {
+        impl<T1: Copy + $trait<T2> + Element + 'static, T2: Scalar + Element + 'static, const N: usize> $trait<T2> for VecN<T1, N> {
+            fn $method(&mut self, rhs: T2) {
+                self.columns.iter_mut().for_each(|column| column.$method(rhs));
+            }
+        }
+    };
+}
+
+vecn_assign!(AddAssign, add_assign, element);
+vecn_assign!(SubAssign, sub_assign, element);
+vecn_assign!(MulAssign, mul_assign, scalar);
+vecn_assign!(DivAssign, div_assign, scalar);
+
+/// The column ops of a named vector, applied field by field as for `VecN`.
+macro_rules! named_assign {
+    ($vec:ident, [$($field:ident),+], $trait:ident, $method:ident, $value:ident) => {
+        impl<'a, T1: Copy + $trait<T2> + Element + 'static, T2: Copy + Element + 'static> $trait<&'a $vec<T2>> for $vec<T1> {
+            fn $method(&mut self, rhs: &'a $vec<T2>) {
+                $(self.$field.$method(&rhs.$field);)+
+            }
+        }
+
+        impl<'a, T1: Copy + $trait<T2> + Element + 'static, T2: Copy + Element + 'static> $trait<&'a Vec1<T2>> for $vec<T1> {
+            fn $method(&mut self, rhs: &'a Vec1<T2>) {
+                $(self.$field.$method(rhs);)+
+            }
+        }
+
+        named_assign!(@$value $vec, [$($field),+], $trait, $method);
+    };
+    (@element $vec:ident, [$($field:ident),+], $trait:ident, $method:ident) => {
+        impl<T: Copy + $trait<T> + Element + 'static> $trait<T> for $vec<T> {
+            fn $method(&mut self, rhs: T) {
+                $(self.$field.$method(rhs);)+
+            }
+        }
+    };
+    (@scalar $vec:ident, [$($field:ident),+], $trait:ident, $method:ident) => {
+        impl<T1: Copy + $trait<T2> + Element + 'static, T2: Scalar + Element + 'static> $trait<T2> for $vec<T1> {
+            fn $method(&mut self, rhs: T2) {
+                $(self.$field.$method(rhs);)+
+            }
+        }
+    };
+}
+
+/// The conversions to and from `VecN` for a struct whose fields are exactly `$n` `Vec1<T>`
+/// columns, its column ops, and the methods that do not depend on the tuple type, forwarding
+/// to `columns`.
+macro_rules! named_columns {
+    ($vec:ident, $n:literal, $($field:ident),+) => {
+        impl<T> From<VecN<T, $n>> for $vec<T> {
+            fn from(vec: VecN<T, $n>) -> Self {
+                let [$($field),+] = vec.columns;
+                $vec { $($field),+ }
+            }
+        }
+
+        impl<T> From<$vec<T>> for VecN<T, $n> {
+            fn from(vec: $vec<T>) -> Self {
+                VecN { columns: [$(vec.$field),+] }
+            }
+        }
+
+        impl<T> $vec<T> {
+            pub fn columns(&self) -> [&Vec1<T>; $n] {
+                [$(&self.$field),+]
+            }
+
+            pub fn columns_mut(&mut self) -> [&mut Vec1<T>; $n] {
+                [$(&mut self.$field),+]
+            }
+
+            pub fn len(&self) -> usize {
+                self.x.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            pub fn truncate(&mut self, len: usize) {
+                $(self.$field.truncate(len);)+
+            }
+
+            pub fn clear(&mut self) {
+                $(self.$field.clear();)+
+            }
+
+            pub fn retain_by_mask(&mut self, mask: &[bool]) {
+                columns::retain_by_mask(self.columns_mut(), mask);
+            }
+
+            pub fn rows(&self) -> impl Iterator<Item=[&T; $n]> + '_ {
+                columns::rows(self.columns())
+            }
+
+            pub fn rows_mut(&mut self) -> impl Iterator<Item=[&mut T; $n]> + '_ {
+                columns::rows_mut(self.columns_mut())
+            }
+
+            pub fn zip_to_value<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: T2, f: F) {
+                columns::zip_to_value(self.columns_mut(), rhs, f);
+            }
+
+            pub fn zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) {
+                columns::zip_to_vec1(self.columns_mut(), rhs, f);
+            }
+
+            pub fn zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) {
+                columns::zip_to_expr(self.columns_mut(), expr, f);
+            }
+
+            pub fn try_zip_to_vec1<T2: Copy, F: Fn(&mut T, T2)>(&mut self, rhs: &Vec1<T2>, f: F) -> Result<(), SimdVecError> {
+                check_len(self.len(), rhs.len())?;
+                self.zip_to_vec1(rhs, f);
+                Ok(())
+            }
+
+            pub fn try_zip_to_expr<E: VExpr, F: Fn(&mut T, E::Output)>(&mut self, expr: &E, f: F) -> Result<(), SimdVecError> {
+                check_size(self.len(), expr.try_size()?)?;
+                self.zip_to_expr(expr, f);
+                Ok(())
+            }
+
+            pub(crate) fn chunked_zip_to_expr<E: VExpr + Element, F: Fn(&mut T, E::Output) + Sync>(&mut self, expr: &E, f: F) where T: Element {
+                columns::chunked_zip_to_expr(self.columns_mut(), expr, f);
+            }
+
+            pub(crate) fn assign_columns<E: VExpr<Output=T>>(&mut self, expr: &E) {
+                columns::assign_columns(self.columns_mut(), expr);
+            }
+
+            pub(crate) fn fused_assign<A: VExpr, B: VExpr>(&mut self, op: FusedOp, a: &A, b: &B) -> bool
+                where
+                    T: 'static,
+                    A::Output: 'static,
+                    B::Output: 'static,
+            {
+                columns::fused_assign(self.columns_mut(), op, a, b)
+            }
+        }
+
+        impl<T> Columns for $vec<T> {
+            type Element = T;
+
+            const DIM: usize = $n;
+
+            fn column(&self, axis: usize) -> &Vec1<T> {
+                self.columns()[axis]
+            }
+
+            fn column_mut(&mut self, axis: usize) -> &mut Vec1<T> {
+                IntoIterator::into_iter(self.columns_mut())
+                    .nth(axis)
+                    .expect("axis out of range")
+            }
+        }
+
+        named_assign!($vec, [$($field),+], AddAssign, add_assign, element);
+        named_assign!($vec, [$($field),+], SubAssign, sub_assign, element);
+        named_assign!($vec, [$($field),+], MulAssign, mul_assign, scalar);
+        named_assign!($vec, [$($field),+], DivAssign, div_assign, scalar);
+    };
+}
+
+named_columns!(Vec2, 2, x, y);
+named_columns!(Vec3, 3, x, y, z);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use physics::*;
+
+    fn vec4(values: [Vec<f64>; 4]) -> VecN<f64, 4> {
+        VecN { columns: values.map(Vec1::from) }
+    }
+
+    #[test]
+    fn insert_remove_and_get() {
+        let mut v = VecN::<i32, 4>::new();
+
+        assert_eq!(InsertOutcome::Appended, v.insert([1, 2, 3, 4], 0));
+        assert_eq!(InsertOutcome::Appended, v.insert([5, 6, 7, 8], 1));
+        assert_eq!(InsertOutcome::Replaced([1, 2, 3, 4]), v.insert([0, 0, 0, 0], 0));
+        assert_eq!(InsertOutcome::OutOfBounds, v.insert([9, 9, 9, 9], 3));
+
+        assert_eq!(Some([&5, &6, &7, &8]), v.get(1));
+        assert_eq!(Some(SwapRemoved { value: [0, 0, 0, 0], moved_from: Some(1) }), v.swap_remove(0));
+        assert_eq!(vec![[5, 6, 7, 8]], v.drain(..).collect::<Vec<_>>());
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut v = vec4([vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0], vec![7.0, 8.0]]);
+
+        v.zip_to_value(2.0, |a, b| *a *= b);
+        assert_eq!(vec![2.0, 4.0], v.columns[0].values);
+
+        // normalize each row of the 4D vectors by their sum
+        v.zip_rows_to_value((), |row, _| {
+            let sum: f64 = row.iter().map(|v| **v).sum();
+            for v in row {
+                *v /= sum;
+            }
+        });
+        assert_eq!(vec![0.0625, 0.1], v.columns[0].values);
+        assert_eq!(vec![0.4375, 0.4], v.columns[3].values);
+    }
+
+    #[test]
+    fn fused_accumulation() {
+        let mut position: VecN<Length, 4> = VecN { columns: [vec![Length::in_meters(1.0)].into(), vec![Length::in_meters(2.0)].into(), vec![Length::in_meters(3.0)].into(), vec![Length::in_meters(4.0)].into()] };
+        let velocity: VecN<Speed, 4> = VecN { columns: [vec![Speed::in_meters_per_second(1.0)].into(), vec![Speed::in_meters_per_second(-1.0)].into(), vec![Speed::in_meters_per_second(0.5)].into(), vec![Speed::in_meters_per_second(0.0)].into()] };
+        let dt = Time::in_seconds(2.0);
+
+        position += &velocity * &dt;
+
+        let expected = [3.0, 0.0, 4.0, 4.0].map(|v| Vec1::from(vec![Length::in_meters(v)]));
+        assert_eq!(expected, position.columns);
+
+        let mut f = vec4([vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0], vec![7.0, 8.0]]);
+        let g = f.clone();
+        f -= &g * &0.5;
+        assert_eq!(vec![2.5, 3.0], f.columns[2].values);
+        assert_eq!(vec![2.5, 3.0], (&f + &f * &0.0).eval().columns[2].values);
+    }
+
+    #[test]
+    fn vec2_and_vec3_share_columns() {
+        let mut v = Vec3 {
+            x: vec![1.0].into(),
+            y: vec![2.0].into(),
+            z: vec![3.0].into(),
+        };
+
+        v.columns_mut()[2].values[0] = 4.0;
+        assert_eq!(vec![4.0], v.z.values);
+
+        let n: VecN<f64, 3> = v.clone().into();
+        assert_eq!(v, Vec3::from(n));
+    }
+
+    #[test]
+    fn mismatched_columns_return_none() {
+        let mut v = Vec2 {
+            x: vec![1, 2].into(),
+            y: vec![3].into(),
+        };
+
+        assert_eq!(Some((&1, &3)), v.get(0));
+        assert_eq!(None, v.get(1));
+        assert_eq!(None, v.get_mut(1));
+        assert_eq!(None, v.swap_remove(0));
+        assert_eq!(None, v.remove(0));
+        assert_eq!(InsertOutcome::OutOfBounds, v.insert(5, 6, 1));
+        assert_eq!(InsertOutcome::OutOfBounds, v.insert_or_resize(5, 6, 4));
+        assert_eq!(vec![1, 2], v.x.values);
+        assert_eq!(vec![3], v.y.values);
+
+        v.retain(|x, _| *x == 1);
+        assert_eq!(vec![1, 2], v.x.values);
+        assert_eq!(vec![3], v.y.values);
+
+        let mut n: VecN<i32, 3> = VecN { columns: [vec![1].into(), vec![2, 3].into(), vec![].into()] };
+        assert_eq!(None, n.get(0));
+        assert_eq!(None, n.remove(0));
+        assert_eq!(0, n.rows().count());
+    }
+}
@@ -0,0 +1,318 @@
+//! Zips any mix of columns and values into a single loop.
+//!
+//! ```
+//! # use simd_vecs::vecs::{Vec1, Vec2};
+//! # use simd_vecs::zip::Zip;
+//! let mut position = Vec2 { x: Vec1::from(vec![0.0]), y: Vec1::from(vec![1.0]) };
+//! let velocity = Vec2 { x: Vec1::from(vec![2.0]), y: Vec1::from(vec![0.0]) };
+//! let drag = Vec1::from(vec![0.5]);
+//!
+//! Zip::new(&mut position)
+//!     .and(&velocity)
+//!     .and(&drag)
+//!     .and_value(0.1)
+//!     .for_each(|(x, y), (vx, vy), k, dt| {
+//!         *x += vx * k * dt;
+//!         *y += vy * k * dt;
+//!     });
+//!
+//! assert_eq!(vec![0.1], position.x.values);
+//! ```
+//!
+//! `Vec2` and `Vec3` sources are passed to the closure as tuples, and all lengths are checked once
+//! before the loop starts.
+
+use crate::error::{check_len, SimdVecError};
+use crate::vecs::{Vec1, Vec2, Vec3};
+use std::iter::{Copied, Map, RepeatN};
+use std::slice::{Iter, IterMut};
+
+/// A column or value that can be zipped, yielding one `Item` per row.
+pub trait ZipSource {
+    type Item;
+    type Iter: Iterator<Item=Self::Item>;
+
+    /// The number of rows, or `None` for values that repeat for every row.
+    fn size(&self) -> Option<usize>;
+
+    fn zip_iter(self, len: usize) -> Self::Iter;
+}
+
+/// A value that is passed to every row of a `Zip`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Value<T>(pub T);
+
+impl<T: Clone> ZipSource for Value<T> {
+    type Item = T;
+    type Iter = RepeatN<T>;
+
+    fn size(&self) -> Option<usize> {
+        None
+    }
+
+    fn zip_iter(self, len: usize) -> Self::Iter {
+        std::iter::repeat_n(self.0, len)
+    }
+}
+
+impl<'a, T: Copy> ZipSource for &'a Vec1<T> {
+    type Item = T;
+    type Iter = Copied<Iter<'a, T>>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.values.iter().copied()
+    }
+}
+
+impl<'a, T> ZipSource for &'a mut Vec1<T> {
+    type Item = &'a mut T;
+    type Iter = IterMut<'a, T>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.values.iter_mut()
+    }
+}
+
+impl<'a, T: Copy> ZipSource for &'a Vec2<T> {
+    type Item = (T, T);
+    type Iter = std::iter::Zip<Copied<Iter<'a, T>>, Copied<Iter<'a, T>>>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.x.values.iter().copied()
+            .zip(self.y.values.iter().copied())
+    }
+}
+
+impl<'a, T> ZipSource for &'a mut Vec2<T> {
+    type Item = (&'a mut T, &'a mut T);
+    type Iter = std::iter::Zip<IterMut<'a, T>, IterMut<'a, T>>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.x.values.iter_mut()
+            .zip(self.y.values.iter_mut())
+    }
+}
+
+type Zip3<A, B, C, Item> = Map<std::iter::Zip<std::iter::Zip<A, B>, C>, fn(((Item, Item), Item)) -> (Item, Item, Item)>;
+
+impl<'a, T: Copy> ZipSource for &'a Vec3<T> {
+    type Item = (T, T, T);
+    type Iter = Zip3<Copied<Iter<'a, T>>, Copied<Iter<'a, T>>, Copied<Iter<'a, T>>, T>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.x.values.iter().copied()
+            .zip(self.y.values.iter().copied())
+            .zip(self.z.values.iter().copied())
+            .map(|((x, y), z)| (x, y, z))
+    }
+}
+
+impl<'a, T> ZipSource for &'a mut Vec3<T> {
+    type Item = (&'a mut T, &'a mut T, &'a mut T);
+    type Iter = Zip3<IterMut<'a, T>, IterMut<'a, T>, IterMut<'a, T>, &'a mut T>;
+
+    fn size(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn zip_iter(self, _len: usize) -> Self::Iter {
+        self.x.values.iter_mut()
+            .zip(self.y.values.iter_mut())
+            .zip(self.z.values.iter_mut())
+            .map(|((x, y), z)| (x, y, z))
+    }
+}
+
+/// Builds up a tuple of sources with `and`, then visits every row with `for_each`.
+#[must_use]
+pub struct Zip<S> {
+    sources: S,
+    len: Option<usize>,
+    mismatch: Option<(usize, usize)>,
+}
+
+impl<A: ZipSource> Zip<(A,)> {
+    pub fn new(a: A) -> Self {
+        Zip {
+            len: a.size(),
+            mismatch: None,
+            sources: (a,),
+        }
+    }
+}
+
+impl<S> Zip<S> {
+    fn check(&self) -> Result<(), SimdVecError> {
+        self.mismatch.map_or(Ok(()), |(expected, found)| check_len(expected, found))
+    }
+}
+
+/// Keeps the shortest length so that values never outlast the columns,
+/// and remembers the first mismatch to report from `for_each`.
+fn merge(len: Option<usize>, mismatch: Option<(usize, usize)>, size: Option<usize>) -> (Option<usize>, Option<(usize, usize)>) {
+    match (len, size) {
+        (Some(len), Some(size)) if len != size => (Some(len.min(size)), mismatch.or(Some((len, size)))),
+        (len, size) => (len.or(size), mismatch),
+    }
+}
+
+macro_rules! zip_and {
+    ($($s:ident $v:ident),+) => {
+        impl<$($s: ZipSource,)+> Zip<($($s,)+)> {
+            pub fn and<Next: ZipSource>(self, source: Next) -> Zip<($($s,)+ Next,)> {
+                let (len, mismatch) = merge(self.len, self.mismatch, source.size());
+                let ($($v,)+) = self.sources;
+
+                Zip {
+                    sources: ($($v,)+ source,),
+                    len,
+                    mismatch,
+                }
+            }
+
+            pub fn and_value<V: Clone>(self, value: V) -> Zip<($($s,)+ Value<V>,)> {
+                self.and(Value(value))
+            }
+        }
+    };
+}
+
+macro_rules! zip_for_each {
+    ($($s:ident $v:ident),+) => {
+        impl<$($s: ZipSource,)+> Zip<($($s,)+)> {
+            pub fn for_each<F: FnMut($($s::Item),+)>(self, mut f: F) {
+                if let Some((expected, found)) = self.mismatch {
+                    assert_len!(expected, found);
+                }
+
+                let len = self.len.unwrap_or(0);
+                let ($($v,)+) = self.sources;
+                let ($(mut $v,)+) = ($($v.zip_iter(len),)+);
+
+                while let ($(Some($v),)+) = ($($v.next(),)+) {
+                    f($($v),+);
+                }
+            }
+
+            pub fn try_for_each<F: FnMut($($s::Item),+)>(self, f: F) -> Result<(), SimdVecError> {
+                self.check()?;
+                self.for_each(f);
+                Ok(())
+            }
+        }
+    };
+}
+
+zip_and!(A a);
+zip_and!(A a, B b);
+zip_and!(A a, B b, C c);
+zip_and!(A a, B b, C c, D d);
+zip_and!(A a, B b, C c, D d, E e);
+zip_and!(A a, B b, C c, D d, E e, G g);
+zip_and!(A a, B b, C c, D d, E e, G g, H h);
+
+zip_for_each!(A a);
+zip_for_each!(A a, B b);
+zip_for_each!(A a, B b, C c);
+zip_for_each!(A a, B b, C c, D d);
+zip_for_each!(A a, B b, C c, D d, E e);
+zip_for_each!(A a, B b, C c, D d, E e, G g);
+zip_for_each!(A a, B b, C c, D d, E e, G g, H h);
+zip_for_each!(A a, B b, C c, D d, E e, G g, H h, J j);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec2(x: Vec<f64>, y: Vec<f64>) -> Vec2<f64> {
+        Vec2 { x: x.into(), y: y.into() }
+    }
+
+    #[test]
+    fn mixed_sources() {
+        let mut position = vec2(vec![0.0, 1.0], vec![0.0, 2.0]);
+        let velocity = vec2(vec![1.0, 1.0], vec![2.0, -1.0]);
+        let mass: Vec1<f64> = vec![1.0, 2.0].into();
+        let mut energy: Vec1<f64> = vec![0.0, 0.0].into();
+
+        Zip::new(&mut position)
+            .and(&mut energy)
+            .and(&velocity)
+            .and(&mass)
+            .and_value(0.5)
+            .for_each(|(x, y), e, (vx, vy), m, dt| {
+                *x += vx * dt;
+                *y += vy * dt;
+                *e = 0.5 * m * (vx * vx + vy * vy);
+            });
+
+        assert_eq!(vec2(vec![0.5, 1.5], vec![1.0, 1.5]), position);
+        assert_eq!(vec![2.5, 2.0], energy.values);
+    }
+
+    #[test]
+    fn vec3_and_values_only() {
+        let mut v = Vec3 {
+            x: vec![1.0, 2.0].into(),
+            y: vec![3.0, 4.0].into(),
+            z: vec![5.0, 6.0].into(),
+        };
+
+        Zip::new(&mut v)
+            .and_value(2.0)
+            .for_each(|(x, y, z), s| {
+                *x *= s;
+                *y *= s;
+                *z *= s;
+            });
+        assert_eq!(vec![10.0, 12.0], v.z.values);
+
+        let mut sum = 0.0;
+        Zip::new(&v).for_each(|(x, y, z)| sum += x + y + z);
+        assert_eq!(42.0, sum);
+
+        let mut count = 0;
+        Zip::new(Value(1)).for_each(|_| count += 1);
+        assert_eq!(0, count);
+    }
+
+    #[test]
+    fn try_for_each_checks_lengths() {
+        let mut a: Vec1<f64> = vec![1.0, 2.0, 3.0].into();
+        let b: Vec1<f64> = vec![1.0, 2.0].into();
+        let c: Vec1<f64> = vec![1.0].into();
+
+        let result = Zip::new(&mut a)
+            .and_value(1.0)
+            .and(&b)
+            .and(&c)
+            .try_for_each(|a, _, b, c| *a = b + c);
+
+        assert_eq!(Err(SimdVecError::LengthMismatch { expected: 3, found: 2 }), result);
+        assert_eq!(vec![1.0, 2.0, 3.0], a.values);
+
+        let b = a.clone();
+        assert_eq!(Ok(()), Zip::new(&mut a).and(&b).try_for_each(|a, b| *a += b));
+        assert_eq!(vec![2.0, 4.0, 6.0], a.values);
+    }
+}
@@ -0,0 +1,117 @@
+use simd_vecs::vecs::{InsertOutcome, SwapRemoved};
+use simd_vecs_derive::Soa;
+
+#[derive(Debug, Copy, Clone, PartialEq, Soa)]
+struct Particle {
+    pos: (f32, f32),
+    mass: f32,
+    charge: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Soa)]
+pub struct Body {
+    pub name: String,
+    pub position: (f64, f64, f64),
+    pub quaternion: [f64; 4],
+}
+
+fn particle(i: f32) -> Particle {
+    Particle { pos: (i, -i), mass: i * 10.0, charge: -1.0 }
+}
+
+#[test]
+fn lockstep_insert_and_remove() {
+    let mut particles = ParticleVec::new();
+    assert!(particles.is_empty());
+
+    particles.push(particle(0.0));
+    particles.push(particle(1.0));
+    assert_eq!(InsertOutcome::Appended, particles.insert(particle(2.0), 2));
+    assert_eq!(InsertOutcome::Replaced(particle(1.0)), particles.insert(particle(3.0), 1));
+    assert_eq!(InsertOutcome::OutOfBounds, particles.insert(particle(4.0), 4));
+
+    assert_eq!(3, particles.len());
+    assert_eq!(vec![0.0, 30.0, 20.0], particles.mass.values);
+    assert_eq!(vec![0.0, -3.0, -2.0], particles.pos.y.values);
+
+    assert_eq!(Some(SwapRemoved { value: particle(0.0), moved_from: Some(2) }), particles.swap_remove(0));
+    assert_eq!(Some(particle(3.0)), particles.remove(1));
+    assert_eq!(None, particles.remove(1));
+    assert_eq!(Ok(()), particles.check_len());
+}
+
+#[test]
+fn row_views() {
+    let mut particles = (0..4).map(|i| particle(i as f32)).collect::<ParticleVec>();
+
+    let p = particles.get(2).unwrap();
+    assert_eq!((&2.0, &-2.0), p.pos);
+    assert_eq!(&20.0, p.mass);
+    assert!(particles.get(4).is_none());
+
+    *particles.get_mut(1).unwrap().charge = 1.0;
+    for p in particles.iter_mut() {
+        *p.pos.0 += *p.mass;
+    }
+
+    let x = particles.iter().map(|p| *p.pos.0).collect::<Vec<_>>();
+    assert_eq!(vec![0.0, 11.0, 22.0, 33.0], x);
+    assert_eq!(vec![-1.0, 1.0, -1.0, -1.0], particles.charge.values);
+}
+
+#[test]
+fn mixed_columns() {
+    let mut bodies = BodyVec::new();
+    bodies.push(Body { name: "a".to_string(), position: (1.0, 2.0, 3.0), quaternion: [1.0, 0.0, 0.0, 0.0] });
+    bodies.push(Body { name: "b".to_string(), position: (4.0, 5.0, 6.0), quaternion: [0.0, 1.0, 0.0, 0.0] });
+
+    let b = bodies.get(1).unwrap();
+    assert_eq!("b", b.name);
+    assert_eq!((&4.0, &5.0, &6.0), b.position);
+    assert_eq!([&0.0, &1.0, &0.0, &0.0], b.quaternion);
+
+    bodies.name.values.pop();
+    assert!(bodies.check_len().is_err());
+}
+
+#[derive(Debug, Clone, PartialEq, Soa)]
+struct Entry {
+    index: u32,
+    value: (f64, f64),
+    r#type: u8,
+}
+
+#[test]
+fn fields_named_like_method_locals() {
+    let mut entries = EntryVec::new();
+    entries.push(Entry { index: 7, value: (1.0, 2.0), r#type: 1 });
+    entries.push(Entry { index: 8, value: (3.0, 4.0), r#type: 2 });
+
+    assert_eq!(InsertOutcome::Replaced(Entry { index: 8, value: (3.0, 4.0), r#type: 2 }), entries.insert(Entry { index: 9, value: (5.0, 6.0), r#type: 3 }, 1));
+    assert_eq!(Some(Entry { index: 7, value: (1.0, 2.0), r#type: 1 }), entries.remove(0));
+    assert_eq!(vec![9], entries.index.values);
+}
+
+#[test]
+fn mismatched_columns_are_left_unchanged() {
+    let mut particles = (0..3).map(|i| particle(i as f32)).collect::<ParticleVec>();
+    particles.charge.values.pop();
+
+    assert_eq!(InsertOutcome::OutOfBounds, particles.insert(particle(5.0), 1));
+    assert_eq!(InsertOutcome::OutOfBounds, particles.insert(particle(5.0), 3));
+    assert_eq!(None, particles.swap_remove(0));
+    assert_eq!(None, particles.remove(2));
+
+    assert_eq!(vec![0.0, 10.0, 20.0], particles.mass.values);
+    assert_eq!(vec![0.0, 1.0, 2.0], particles.pos.x.values);
+    assert_eq!(vec![-1.0, -1.0], particles.charge.values);
+}
+
+#[test]
+#[should_panic(expected = "length mismatch: expected 3, found 2")]
+fn push_panics_on_mismatched_columns() {
+    let mut particles = (0..3).map(|i| particle(i as f32)).collect::<ParticleVec>();
+    particles.charge.values.pop();
+
+    particles.push(particle(3.0));
+}